@@ -1906,6 +1906,11 @@ impl BlockChainClient for Client {
 		self.importer.miner.ready_transactions(number, timestamp)
 	}
 
+	fn pending_transaction(&self, hash: &H256) -> Option<PendingTransaction> {
+		let best_block = self.chain.read().best_block_number();
+		self.importer.miner.transaction(best_block, hash)
+	}
+
 	fn queue_consensus_message(&self, message: Bytes) {
 		let channel = self.io_channel.lock().clone();
 		if let Err(e) = channel.send(ClientIoMessage::NewMessage(message)) {
@@ -113,6 +113,8 @@ pub struct TestBlockChainClient {
 	pub traces: RwLock<Option<Vec<LocalizedTrace>>>,
 	/// Pruning history size to report.
 	pub history: RwLock<Option<u64>>,
+	/// Chain id used for signing transactions.
+	pub chain_id: RwLock<Option<u64>>,
 }
 
 /// Used for generating test client blocks.
@@ -179,6 +181,7 @@ impl TestBlockChainClient {
 			first_block: RwLock::new(None),
 			traces: RwLock::new(None),
 			history: RwLock::new(None),
+			chain_id: RwLock::new(None),
 		};
 
 		// insert genesis hash.
@@ -611,8 +614,11 @@ impl BlockChainClient for TestBlockChainClient {
 		self.execution_result.read().clone().unwrap()
 	}
 
-	fn replay_block_transactions(&self, _block: BlockId, _analytics: CallAnalytics) -> Result<Box<Iterator<Item = Executed>>, CallError> {
-		Ok(Box::new(self.execution_result.read().clone().unwrap().into_iter()))
+	fn replay_block_transactions(&self, block: BlockId, _analytics: CallAnalytics) -> Result<Box<Iterator<Item = Executed>>, CallError> {
+		let executed = self.execution_result.read().clone().unwrap()?;
+		// each transaction of a known block is replayed with the same result
+		let count = self.block_body(block).map_or(1, |body| body.transactions_count());
+		Ok(Box::new(::std::iter::repeat(executed).take(count)))
 	}
 
 	fn block_total_difficulty(&self, _id: BlockId) -> Option<U256> {
@@ -666,6 +672,12 @@ impl BlockChainClient for TestBlockChainClient {
 
 	fn logs(&self, filter: Filter) -> Vec<LocalizedLogEntry> {
 		let mut logs = self.logs.read().clone();
+		// filters of a single block hash only get logs of that block
+		if let (&BlockId::Hash(ref from), &BlockId::Hash(ref to)) = (&filter.from_block, &filter.to_block) {
+			if from == to {
+				logs.retain(|log| log.block_hash == *from);
+			}
+		}
 		let len = logs.len();
 		match filter.limit {
 			Some(limit) if limit <= len => logs.split_off(len - limit),
@@ -814,7 +826,11 @@ impl BlockChainClient for TestBlockChainClient {
 		self.miner.ready_transactions(info.best_block_number, info.best_block_timestamp)
 	}
 
-	fn signing_chain_id(&self) -> Option<u64> { None }
+	fn pending_transaction(&self, hash: &H256) -> Option<PendingTransaction> {
+		self.miner.transaction(self.chain_info().best_block_number, hash)
+	}
+
+	fn signing_chain_id(&self) -> Option<u64> { *self.chain_id.read() }
 
 	fn mode(&self) -> Mode { Mode::Active }
 
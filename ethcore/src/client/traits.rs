@@ -317,6 +317,9 @@ pub trait BlockChainClient : Sync + Send + AccountData + BlockChain + CallContra
 	/// List all transactions that are allowed into the next block.
 	fn ready_transactions(&self) -> Vec<PendingTransaction>;
 
+	/// Get pending transaction with given hash, including transactions not ready for the next block yet.
+	fn pending_transaction(&self, hash: &H256) -> Option<PendingTransaction>;
+
 	/// Get all local transactions
 	fn local_transactions(&self) -> Vec<H256>;
 
@@ -15,7 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::time::{Instant, Duration};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 use account_provider::{AccountProvider, SignError as AccountError};
@@ -1004,6 +1004,18 @@ impl MinerService for Miner {
 		}
 	}
 
+	fn recent_pending_transactions_hashes(&self, best_block: BlockNumber) -> Vec<H256> {
+		let mut hashes = self.pending_transactions_hashes(best_block);
+		// transactions of the pending block which aren't queued anymore come last
+		let positions = self.transaction_queue.read().recent_pending_hashes()
+			.into_iter()
+			.enumerate()
+			.map(|(position, hash)| (hash, position))
+			.collect::<HashMap<_, _>>();
+		hashes.sort_by_key(|hash| positions.get(hash).cloned().unwrap_or(usize::max_value()));
+		hashes
+	}
+
 	fn pending_transactions_hashes(&self, best_block: BlockNumber) -> Vec<H256> {
 		let queue = self.transaction_queue.read();
 		match self.options.pending_set {
@@ -124,6 +124,9 @@ pub trait MinerService : Send + Sync {
 	/// Returns hashes of transactions currently in pending
 	fn pending_transactions_hashes(&self, best_block: BlockNumber) -> Vec<H256>;
 
+	/// Returns hashes of transactions currently in pending, the most recently queued first.
+	fn recent_pending_transactions_hashes(&self, best_block: BlockNumber) -> Vec<H256>;
+
 	/// Removes all transactions from the queue and restart mining operation.
 	fn clear_and_reset<C: MiningBlockChainClient>(&self, chain: &C);
 
@@ -1186,6 +1186,15 @@ impl TransactionQueue {
 			.collect()
 	}
 
+	/// Returns hashes of all transactions from current, the most recently inserted first.
+	pub fn recent_pending_hashes(&self) -> Vec<H256> {
+		let mut transactions = self.current.by_priority.iter().collect::<Vec<_>>();
+		transactions.sort_by(|a, b| b.insertion_id.cmp(&a.insertion_id));
+		transactions.into_iter()
+			.map(|t| t.hash)
+			.collect()
+	}
+
 	/// Returns true if there is at least one local transaction pending
 	pub fn has_local_pending_transactions(&self) -> bool {
 		self.current.by_priority.iter().any(|tx| tx.origin == TransactionOrigin::Local)
@@ -2215,6 +2224,21 @@ pub mod test {
 		assert_eq!(top.len(), 2);
 	}
 
+	#[test]
+	fn should_return_recent_pending_hashes_first() {
+		// given
+		let mut txq = TransactionQueue::default();
+
+		let (tx, tx2) = new_tx_pair_default(1.into(), 0.into());
+
+		// when
+		txq.add(tx.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+		txq.add(tx2.clone(), TransactionOrigin::External, 0, None, &default_tx_provider()).unwrap();
+
+		// then
+		assert_eq!(txq.recent_pending_hashes(), vec![tx2.hash(), tx.hash()]);
+	}
+
 	#[test]
 	fn should_put_transaction_to_futures_if_gap_detected() {
 		// given
@@ -191,6 +191,36 @@ fn to_modules(apis: &HashSet<Api>) -> BTreeMap<String, String> {
 	modules
 }
 
+/// Optional features of a full node's `apis`, as reported by `web3_clientVersion`.
+/// Filters and subscriptions are not available through the generic pubsub.
+fn full_features(apis: &HashSet<Api>, for_generic_pubsub: bool) -> Vec<String> {
+	let mut features = Vec::new();
+	if apis.contains(&Api::Eth) && !for_generic_pubsub {
+		features.push("filters");
+	}
+	if apis.contains(&Api::EthPubSub) && !for_generic_pubsub {
+		features.push("subscriptions");
+		features.push("returnDataSubscriptions");
+	}
+	if apis.contains(&Api::Traces) {
+		features.push("returnDataReplay");
+	}
+	features.into_iter().map(Into::into).collect()
+}
+
+/// Optional features of a light node's `apis`, as reported by `web3_clientVersion`.
+/// Return data is neither replayed nor pushed to subscribers.
+fn light_features(apis: &HashSet<Api>, for_generic_pubsub: bool) -> Vec<String> {
+	let mut features = Vec::new();
+	if apis.contains(&Api::Eth) && !for_generic_pubsub {
+		features.push("filters");
+	}
+	if apis.contains(&Api::EthPubSub) {
+		features.push("subscriptions");
+	}
+	features.into_iter().map(Into::into).collect()
+}
+
 /// RPC dependencies can be used to initialize RPC endpoints from APIs.
 pub trait Dependencies {
 	type Notifier: ActivityNotifier;
@@ -262,10 +292,18 @@ impl FullDependencies {
 			nonces.clone(),
 			self.gas_price_percentile,
 		);
+		// filters are inspected through `parity` and managed through `parity_set` as well
+		let filter_client = EthFilterClient::new_with_options(
+			self.client.clone(),
+			self.miner.clone(),
+			EthFilterOptions::with(|options| options.filter_id_nonce = true),
+		);
+		let filters = filter_client.filters();
+		let mut filter_client = Some(filter_client);
 		for api in apis {
 			match *api {
 				Api::Web3 => {
-					handler.extend_with(Web3Client::new().to_delegate());
+					handler.extend_with(Web3Client::with_capabilities(to_modules(&apis), full_features(&apis, for_generic_pubsub)).to_delegate());
 				},
 				Api::Net => {
 					handler.extend_with(NetClient::new(&self.sync).to_delegate());
@@ -288,15 +326,16 @@ impl FullDependencies {
 					handler.extend_with(client.to_delegate());
 
 					if !for_generic_pubsub {
-						let filter_client = EthFilterClient::new(self.client.clone(), self.miner.clone());
-						handler.extend_with(filter_client.to_delegate());
+						if let Some(filter_client) = filter_client.take() {
+							handler.extend_with(filter_client.to_delegate());
+						}
 
 						add_signing_methods!(EthSigning, handler, self, nonces.clone());
 					}
 				},
 				Api::EthPubSub => {
 					if !for_generic_pubsub {
-						let client = EthPubSubClient::new(self.client.clone(), self.remote.clone());
+						let client = EthPubSubClient::new(self.client.clone(), self.remote.clone(), self.pool.clone());
 						let h = client.handler();
 						self.miner.add_transactions_listener(Box::new(move |hashes| if let Some(h) = h.upgrade() {
 							h.new_transactions(hashes);
@@ -332,6 +371,7 @@ impl FullDependencies {
 						signer,
 						self.dapps_address.clone(),
 						self.ws_address.clone(),
+						filters.clone(),
 					).to_delegate());
 
 					if !for_generic_pubsub {
@@ -358,10 +398,11 @@ impl FullDependencies {
 						self.dapps_service.clone(),
 						self.fetch.clone(),
 						self.pool.clone(),
+						filters.clone(),
 					).to_delegate())
 				},
 				Api::Traces => {
-					handler.extend_with(TracesClient::new(&self.client).to_delegate())
+					handler.extend_with(TracesClient::new(&self.client, self.pool.clone()).to_delegate())
 				},
 				Api::Rpc => {
 					let modules = to_modules(&apis);
@@ -478,29 +519,34 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 			}
 		}
 
+		// filters are inspected through `parity` and managed through `parity_set` as well
+		let eth_client = light::EthClient::new(
+			self.sync.clone(),
+			self.client.clone(),
+			self.on_demand.clone(),
+			self.transaction_queue.clone(),
+			self.secret_store.clone(),
+			self.cache.clone(),
+			self.gas_price_percentile,
+		);
+		let filters = eth_client.filters();
+		let mut eth_client = Some(eth_client);
 		for api in apis {
 			match *api {
 				Api::Web3 => {
-					handler.extend_with(Web3Client::new().to_delegate());
+					handler.extend_with(Web3Client::with_capabilities(to_modules(&apis), light_features(&apis, for_generic_pubsub)).to_delegate());
 				},
 				Api::Net => {
 					handler.extend_with(light::NetClient::new(self.sync.clone()).to_delegate());
 				},
 				Api::Eth => {
-					let client = light::EthClient::new(
-						self.sync.clone(),
-						self.client.clone(),
-						self.on_demand.clone(),
-						self.transaction_queue.clone(),
-						self.secret_store.clone(),
-						self.cache.clone(),
-						self.gas_price_percentile,
-					);
-					handler.extend_with(Eth::to_delegate(client.clone()));
+					if let Some(client) = eth_client.take() {
+						handler.extend_with(Eth::to_delegate(client.clone()));
 
-					if !for_generic_pubsub {
-						handler.extend_with(EthFilter::to_delegate(client));
-						add_signing_methods!(EthSigning, handler, self);
+						if !for_generic_pubsub {
+							handler.extend_with(EthFilter::to_delegate(client));
+							add_signing_methods!(EthSigning, handler, self);
+						}
 					}
 				},
 				Api::EthPubSub => {
@@ -510,6 +556,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 						self.sync.clone(),
 						self.cache.clone(),
 						self.remote.clone(),
+						self.pool.clone(),
 						self.gas_price_percentile,
 					);
 					self.client.add_listener(client.handler() as Weak<_>);
@@ -545,6 +592,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 						self.dapps_address.clone(),
 						self.ws_address.clone(),
 						self.gas_price_percentile,
+						filters.clone(),
 					).to_delegate());
 
 					if !for_generic_pubsub {
@@ -569,6 +617,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 						self.dapps_service.clone(),
 						self.fetch.clone(),
 						self.pool.clone(),
+						filters.clone(),
 					).to_delegate())
 				},
 				Api::Traces => {
@@ -677,7 +726,7 @@ impl ApiSet {
 
 #[cfg(test)]
 mod test {
-	use super::{Api, ApiSet};
+	use super::{Api, ApiSet, full_features, light_features};
 
 	#[test]
 	fn test_api_parsing() {
@@ -760,6 +809,20 @@ mod test {
 		].into_iter().collect()));
 	}
 
+	#[test]
+	fn test_full_features() {
+		let apis = ApiSet::List(vec![Api::Web3, Api::Eth, Api::EthPubSub, Api::Traces].into_iter().collect()).list_apis();
+		assert_eq!(full_features(&apis, false), vec!["filters", "subscriptions", "returnDataSubscriptions", "returnDataReplay"]);
+		assert_eq!(full_features(&apis, true), vec!["returnDataReplay"]);
+	}
+
+	#[test]
+	fn test_light_features() {
+		let apis = ApiSet::List(vec![Api::Web3, Api::Eth, Api::EthPubSub, Api::Traces].into_iter().collect()).list_apis();
+		assert_eq!(light_features(&apis, false), vec!["filters", "subscriptions"]);
+		assert_eq!(light_features(&apis, true), vec!["subscriptions"]);
+	}
+
 	#[test]
 	fn test_safe_parsing() {
 		assert_eq!("safe".parse::<ApiSet>().unwrap(), ApiSet::List(vec![
@@ -22,8 +22,10 @@
 extern crate futures;
 
 extern crate ansi_term;
+extern crate base64;
 extern crate cid;
 extern crate crypto as rust_crypto;
+extern crate flate2;
 extern crate futures_cpupool;
 extern crate itertools;
 extern crate multihash;
@@ -42,11 +42,19 @@ mod codes {
 	pub const REQUEST_REJECTED: i64 = -32040;
 	pub const REQUEST_REJECTED_LIMIT: i64 = -32041;
 	pub const REQUEST_NOT_FOUND: i64 = -32042;
+	pub const REQUEST_TIMEOUT: i64 = -32043;
 	pub const ENCRYPTION_ERROR: i64 = -32055;
 	pub const ENCODING_ERROR: i64 = -32058;
 	pub const FETCH_ERROR: i64 = -32060;
 	pub const NO_LIGHT_PEERS: i64 = -32065;
 	pub const DEPRECATED: i64 = -32070;
+	// Filter errors from [-32089, -32080]
+	pub const FILTER_NOT_FOUND: i64 = -32080;
+	pub const FILTER_WRONG_TYPE: i64 = -32081;
+	pub const FILTER_LIMIT_REACHED: i64 = -32082;
+	pub const FILTER_RANGE_TOO_LARGE: i64 = -32083;
+	pub const FILTER_FROM_FUTURE_BLOCK: i64 = -32084;
+	pub const FILTER_POLLED_TOO_OFTEN: i64 = -32085;
 }
 
 pub fn unimplemented(details: Option<String>) -> Error {
@@ -132,6 +140,14 @@ pub fn invalid_params<T: fmt::Debug>(param: &str, details: T) -> Error {
 	}
 }
 
+pub fn unsupported_block_tag() -> Error {
+	Error {
+		code: ErrorCode::InvalidParams,
+		message: "`safe` and `finalized` blocks are not supported.".into(),
+		data: None,
+	}
+}
+
 pub fn execution<T: fmt::Debug>(data: T) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
@@ -365,6 +381,62 @@ pub fn unknown_block() -> Error {
 	}
 }
 
+pub fn filter_not_found() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::FILTER_NOT_FOUND),
+		message: "Filter not found".into(),
+		data: None,
+	}
+}
+
+pub fn filter_wrong_type(expected: &str) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::FILTER_WRONG_TYPE),
+		message: format!("Filter is not a {} filter", expected),
+		data: None,
+	}
+}
+
+pub fn filter_range_too_large(max_block_range: u64) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::FILTER_RANGE_TOO_LARGE),
+		message: "Filter block range is too large.".into(),
+		data: Some(Value::String(format!("Maximal range is {} blocks", max_block_range))),
+	}
+}
+
+pub fn filter_from_future_block(best_block: u64, max_future_blocks: u64) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::FILTER_FROM_FUTURE_BLOCK),
+		message: "Filter starts too far beyond the best block.".into(),
+		data: Some(Value::String(format!("Best block is {}, filters may start at most {} blocks later", best_block, max_future_blocks))),
+	}
+}
+
+pub fn filter_polled_too_often() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::FILTER_POLLED_TOO_OFTEN),
+		message: "Filter changes have been requested too often.".into(),
+		data: None,
+	}
+}
+
+pub fn request_timeout() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REQUEST_TIMEOUT),
+		message: "Request has timed out.".into(),
+		data: None,
+	}
+}
+
+pub fn filter_limit_reached() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::FILTER_LIMIT_REACHED),
+		message: "Filter has been rejected because of filters limit.".into(),
+		data: None,
+	}
+}
+
 pub fn no_light_peers() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::NO_LIGHT_PEERS),
@@ -208,7 +208,8 @@ impl LightFetch {
 			BlockNumber::Pending => {
 				warn!("`Pending` is deprecated and may be removed in future versions. Falling back to `Latest`");
 				BlockId::Latest
-			}
+			},
+			BlockNumber::Safe | BlockNumber::Finalized => return Box::new(future::err(errors::unsupported_block_tag())),
 		};
 
 		let from = req.from.unwrap_or(Address::zero());
@@ -32,6 +32,7 @@ mod network_settings;
 mod poll_filter;
 mod poll_manager;
 mod requests;
+mod return_data;
 mod signer;
 mod signing_queue;
 mod subscribers;
@@ -39,8 +40,9 @@ mod subscription_manager;
 
 pub use self::dispatch::{Dispatcher, FullDispatcher};
 pub use self::network_settings::NetworkSettings;
-pub use self::poll_manager::PollManager;
-pub use self::poll_filter::{PollFilter, limit_logs};
+pub use self::poll_manager::{PollManager, PollError, PollTimer, SequentialIds, POLL_LIMIT};
+pub use self::poll_filter::{PollFilter, SyncPollFilter, SharedFilters, FILTER_KINDS, HashHistory, ReorgDetector, limit_logs, append_limited_logs, limit_size, log_keys, test_filter};
+pub use self::return_data::{replay_block as replay_block_return_data, replay_block_receipts, compress as compress_return_data, encode as encode_return_data};
 pub use self::requests::{
 	TransactionRequest, FilledTransactionRequest, ConfirmationRequest, ConfirmationPayload, CallRequest,
 };
@@ -1,27 +1,618 @@
 //! Helper type with all filter state data.
 
-use std::collections::HashSet;
-use ethereum_types::H256;
-use v1::types::{Filter, Log};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use ethcore::log_entry::LogEntry;
+use ethereum_types::{H256, U256 as EthU256};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json;
+use v1::helpers::PollManager;
+use v1::types::{BlockNumber as RpcBlockNumber, Filter, FilterState, LimitDirection, Log, H256 as RpcH256, U256 as RpcU256};
 
 pub type BlockNumber = u64;
 
+/// Names of all filter types, as returned by `PollFilter::kind`.
+pub const FILTER_KINDS: &[&str] = &["block", "blockHeaders", "pendingTransaction", "logs"];
+
 /// Filter state.
 #[derive(Clone)]
 pub enum PollFilter {
-	/// Number of last block which client was notified about.
-	Block(BlockNumber),
-	/// Hashes of all transactions which client was notified about.
-	PendingTransaction(Vec<H256>),
-	/// Number of From block number, pending logs and log filter itself.
-	Logs(BlockNumber, HashSet<Log>, Filter)
+	/// Block filter state.
+	Block {
+		/// Number of the next block which client should be notified about.
+		next_block: BlockNumber,
+		/// Numbers and hashes of the most recent blocks which client was notified about,
+		/// used to detect chain reorganisations.
+		recent_hashes: VecDeque<(BlockNumber, H256)>,
+		/// Maximal number of hashes returned by a single poll.
+		limit: Option<usize>,
+		/// Best block at the time the filter was created.
+		created_at: BlockNumber,
+	},
+	/// Block headers filter state.
+	BlockHeaders {
+		/// Number of the next block whose header client should be notified about.
+		next_block: BlockNumber,
+		/// Best block at the time the filter was created.
+		created_at: BlockNumber,
+	},
+	/// Pending transactions filter state.
+	PendingTransaction {
+		/// Hashes of all transactions which client was notified about.
+		previous_hashes: HashSet<H256>,
+		/// Hashes of reported transactions which recently left the pool.
+		/// They are not reported again if they come back.
+		evicted_hashes: HashHistory,
+		/// Hashes of reported transactions which left the pool because they were mined.
+		/// Empty unless `mark_returned` is set.
+		mined_hashes: HashHistory,
+		/// Whether full transactions should be returned instead of hashes.
+		full: bool,
+		/// Maximal number of transactions returned by a single poll.
+		limit: Option<usize>,
+		/// Whether the most recently queued transactions are returned first.
+		newest_first: bool,
+		/// Whether hashes are flagged when a reorganisation returned the transaction to the pool.
+		mark_returned: bool,
+		/// Best block at the time the filter was created.
+		created_at: BlockNumber,
+	},
+	/// Log filter state.
+	Logs {
+		/// Number of the first block from which logs are retrieved.
+		block_number: BlockNumber,
+		/// Last block the client was notified about,
+		/// used to detect chain reorganisations.
+		last_block: ReorgDetector,
+		/// Pending logs the client was notified about.
+		previous_logs: HashSet<LogKey>,
+		/// Log filter itself, shared by all filters with identical criteria.
+		filter: Arc<Filter>,
+		/// Best block at the time the filter was created.
+		created_at: BlockNumber,
+		/// Whether logs are being fetched for a poll of the filter,
+		/// overlapping polls are rejected meanwhile.
+		polling: bool,
+	},
+}
+
+impl PollFilter {
+	/// Returns the name of the filter type.
+	pub fn kind(&self) -> &'static str {
+		match *self {
+			PollFilter::Block { .. } => "block",
+			PollFilter::BlockHeaders { .. } => "blockHeaders",
+			PollFilter::PendingTransaction { .. } => "pendingTransaction",
+			PollFilter::Logs { .. } => "logs",
+		}
+	}
+
+	/// Returns the number of the best block at the time the filter was created.
+	pub fn created_at(&self) -> BlockNumber {
+		match *self {
+			PollFilter::Block { created_at, .. } |
+			PollFilter::BlockHeaders { created_at, .. } |
+			PollFilter::PendingTransaction { created_at, .. } |
+			PollFilter::Logs { created_at, .. } => created_at,
+		}
+	}
+
+	/// Returns the log filter, if this is a log filter.
+	pub fn log_filter(&self) -> Option<&Filter> {
+		match *self {
+			PollFilter::Logs { ref filter, .. } => Some(&**filter),
+			_ => None,
+		}
+	}
+
+	/// Returns the type, criteria and cursor of the filter, leaving out state
+	/// which is only meaningful to this node.
+	pub fn state(&self) -> FilterState {
+		match *self {
+			PollFilter::Block { next_block, limit, .. } => FilterState::Block {
+				next_block: next_block.into(),
+				limit: limit,
+			},
+			PollFilter::BlockHeaders { next_block, .. } => FilterState::BlockHeaders {
+				next_block: next_block.into(),
+			},
+			PollFilter::PendingTransaction { full, limit, newest_first, mark_returned, .. } => FilterState::PendingTransaction {
+				full: full,
+				limit: limit,
+				newest_first: newest_first,
+				mark_returned: mark_returned,
+			},
+			PollFilter::Logs { block_number, ref filter, .. } => FilterState::Logs {
+				filter: (**filter).clone(),
+				next_block: block_number.into(),
+			},
+		}
+	}
+}
+
+/// Filter state shared by the poll manager and requests polling the filter.
+///
+/// Each filter is locked on its own, so that distinct filters can be polled concurrently
+/// while the poll manager is only locked to look them up.
+#[derive(Clone)]
+pub struct SyncPollFilter(Arc<Mutex<PollFilter>>);
+
+impl SyncPollFilter {
+	/// Creates new shared filter state.
+	pub fn new(filter: PollFilter) -> Self {
+		SyncPollFilter(Arc::new(Mutex::new(filter)))
+	}
+
+	/// Runs `f` with the filter state locked.
+	pub fn modify<F, R>(&self, f: F) -> R where
+		F: FnOnce(&mut PollFilter) -> R,
+	{
+		f(&mut self.0.lock())
+	}
+
+	/// Returns true if both handles share the same filter state.
+	pub fn is_same(&self, other: &SyncPollFilter) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+}
+
+/// Filters installed through a filter API instance, shared with the APIs managing them.
+pub type SharedFilters = Arc<Mutex<PollManager<SyncPollFilter>>>;
+
+/// Remembers the last block seen by a filter and tells whether
+/// it has been replaced by a chain reorganisation since.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReorgDetector {
+	last_seen: Option<(BlockNumber, H256)>,
+}
+
+impl ReorgDetector {
+	/// Returns the number and hash of the last seen block.
+	pub fn last_seen(&self) -> Option<(BlockNumber, H256)> {
+		self.last_seen
+	}
+
+	/// Records the given block as the last one seen.
+	pub fn update(&mut self, number: BlockNumber, hash: H256) {
+		self.last_seen = Some((number, hash));
+	}
+
+	/// Returns the hash of the last seen block if it's no longer canonical.
+	/// `is_canonical` tells whether the block with given hash is on the canonical chain.
+	pub fn reorged<F>(&self, is_canonical: F) -> Option<H256> where
+		F: FnOnce(H256) -> bool,
+	{
+		let (_, hash) = self.last_seen?;
+		match is_canonical(hash) {
+			true => None,
+			false => Some(hash),
+		}
+	}
+}
+
+/// Hashes in the order they were added, most recent last,
+/// indexed so that lookups don't scan the whole history.
+#[derive(Debug, Default, Clone)]
+pub struct HashHistory {
+	order: VecDeque<H256>,
+	index: HashSet<H256>,
+}
+
+impl HashHistory {
+	/// Returns the number of remembered hashes.
+	pub fn len(&self) -> usize {
+		self.order.len()
+	}
+
+	/// Returns true if no hashes are remembered.
+	pub fn is_empty(&self) -> bool {
+		self.order.is_empty()
+	}
+
+	/// Returns true if the given hash is remembered.
+	pub fn contains(&self, hash: &H256) -> bool {
+		self.index.contains(hash)
+	}
+
+	/// Remembers the given hash as the most recent one, unless it's already remembered.
+	pub fn push(&mut self, hash: H256) {
+		if self.index.insert(hash) {
+			self.order.push_back(hash);
+		}
+	}
+
+	/// Forgets all given hashes.
+	pub fn remove_all<'a, I>(&mut self, hashes: I) where
+		I: IntoIterator<Item = &'a H256>,
+	{
+		let len = self.index.len();
+		for hash in hashes {
+			self.index.remove(hash);
+		}
+		if self.index.len() != len {
+			let index = &self.index;
+			self.order.retain(|hash| index.contains(hash));
+		}
+	}
+
+	/// Forgets the oldest hashes until at most `len` are remembered.
+	pub fn truncate_oldest(&mut self, len: usize) {
+		while self.order.len() > len {
+			if let Some(hash) = self.order.pop_front() {
+				self.index.remove(&hash);
+			}
+		}
+	}
+}
+
+impl Extend<H256> for HashHistory {
+	fn extend<I: IntoIterator<Item = H256>>(&mut self, hashes: I) {
+		for hash in hashes {
+			self.push(hash);
+		}
+	}
+}
+
+/// Identity of a log: block hash, log index, transaction hash and
+/// the position of the log among logs of the same transaction.
+pub type LogKey = (Option<RpcH256>, Option<RpcU256>, Option<RpcH256>, usize);
+
+/// Returns keys identifying given logs.
+///
+/// Pending logs don't have a block hash nor a log index yet,
+/// so logs of the same transaction are told apart by their order.
+pub fn log_keys(logs: &[Log]) -> Vec<LogKey> {
+	let mut positions = HashMap::new();
+	logs.iter()
+		.map(|log| {
+			let id = (log.block_hash.clone(), log.log_index.clone(), log.transaction_hash.clone());
+			let position = positions.entry(id.clone()).or_insert(0);
+			let key = (id.0, id.1, id.2, *position);
+			*position += 1;
+			key
+		})
+		.collect()
+}
+
+/// Sorts logs in canonical order: logs removed by a chain reorganisation
+/// come first, then mined logs by block number, transaction index and log index,
+/// and pending logs last. The sort is stable.
+fn sort_logs(logs: &mut [Log]) {
+	let number = |n: Option<RpcU256>| n.map(Into::<EthU256>::into);
+	logs.sort_by_key(|log| (
+		!log.removed,
+		log.block_number.is_none(),
+		number(log.block_number),
+		number(log.transaction_index),
+		number(log.log_index),
+	));
 }
 
-/// Returns only last `n` logs
-pub fn limit_logs(mut logs: Vec<Log>, limit: Option<usize>) -> Vec<Log> {
+/// Returns only last `n` logs, or first `n` logs if `direction` is `First`, in canonical order.
+///
+/// Logs removed by a chain reorganisation are always returned and don't count
+/// towards the limit, so that the client learns about the reorganisation.
+pub fn limit_logs(mut logs: Vec<Log>, limit: Option<usize>, direction: LimitDirection) -> Vec<Log> {
+	sort_logs(&mut logs);
+	let limit = match limit {
+		Some(limit) => limit,
+		None => return logs,
+	};
+
+	// removed logs are sorted first
+	let removed = logs.iter().take_while(|log| log.removed).count();
 	let len = logs.len();
-	match limit {
-		Some(limit) if len >= limit => logs.split_off(len - limit),
+	match direction {
+		LimitDirection::Last if len - removed > limit => {
+			logs.drain(removed..len - limit);
+			logs
+		},
+		LimitDirection::First => {
+			logs.truncate(removed.saturating_add(limit));
+			logs
+		},
 		_ => logs,
 	}
 }
+
+/// Appends `new` logs to `logs` and returns only `n` of them, kept from the end given by `direction`.
+///
+/// Equivalent to `limit_logs` called on the concatenation, but never holds
+/// more than `2 * n` logs besides the removed ones in the result buffer.
+pub fn append_limited_logs(logs: Vec<Log>, new: Vec<Log>, limit: Option<usize>, direction: LimitDirection) -> Vec<Log> {
+	let mut logs = limit_logs(logs, limit, direction);
+	logs.extend(limit_logs(new, limit, direction));
+	limit_logs(logs, limit, direction)
+}
+
+/// Returns as many `items` as fit in `max_size` bytes once serialized as a JSON array, kept
+/// from the end given by `direction` in their original order, and whether any were left out.
+pub fn limit_size<T: Serialize>(mut items: Vec<T>, max_size: Option<usize>, direction: LimitDirection) -> (Vec<T>, bool) {
+	let max_size = match max_size {
+		Some(max_size) => max_size,
+		None => return (items, false),
+	};
+
+	// the opening bracket, then each item followed by a comma or the closing bracket
+	let len = items.len();
+	let mut size = 1;
+	let mut fitting = 0;
+	while fitting < len {
+		let item = match direction {
+			LimitDirection::First => &items[fitting],
+			LimitDirection::Last => &items[len - 1 - fitting],
+		};
+		size += serde_json::to_vec(item).expect("RPC types are always serializable; qed").len() + 1;
+		if size > max_size {
+			break;
+		}
+		fitting += 1;
+	}
+
+	let items = match direction {
+		LimitDirection::First => {
+			items.truncate(fitting);
+			items
+		},
+		LimitDirection::Last => items.split_off(len - fitting),
+	};
+	(items, fitting < len)
+}
+
+/// Tells whether the filter would match the given log, without looking the log up.
+///
+/// Address and topic criteria are applied by the client's log filter, like for logs it finds.
+/// Block tags can't be resolved without the chain, so only numeric bounds and `earliest`
+/// restrict the block range, and pending logs are not restricted by it at all.
+pub fn test_filter(filter: &Filter, log: &Log) -> bool {
+	if filter.matches_nothing() {
+		return false;
+	}
+
+	let block_hash = log.block_hash.as_ref();
+	if filter.block_hash.as_ref().map_or(false, |hash| block_hash != Some(hash)) ||
+		filter.block_hashes.as_ref().map_or(false, |hashes| !block_hash.map_or(false, |hash| hashes.contains(hash))) ||
+		filter.transaction_hash.as_ref().map_or(false, |hash| log.transaction_hash.as_ref() != Some(hash))
+	{
+		return false;
+	}
+
+	if let Some(number) = log.block_number {
+		let number: EthU256 = number.into();
+		let after_from = match filter.from_block {
+			Some(RpcBlockNumber::Num(from)) => number >= EthU256::from(from),
+			_ => true,
+		};
+		let before_to = match filter.to_block {
+			Some(RpcBlockNumber::Num(to)) => number <= EthU256::from(to),
+			Some(RpcBlockNumber::Earliest) => number.is_zero(),
+			_ => true,
+		};
+		if !after_from || !before_to {
+			return false;
+		}
+	}
+
+	let criteria = Filter {
+		from_block: None,
+		to_block: None,
+		block_hash: None,
+		address: filter.address.clone(),
+		topics: filter.topics.clone(),
+		limit: None,
+		exclude_pending: None,
+		limit_direction: None,
+		transaction_hash: None,
+		block_hashes: None,
+	};
+	let entry = LogEntry {
+		address: log.address.clone().into(),
+		topics: log.topics.iter().cloned().map(Into::into).collect(),
+		data: log.data.clone().into_vec(),
+	};
+	criteria.try_into_eth_filter().map_or(false, |criteria| criteria.matches(&entry))
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{H160, H256, U256};
+	use serde_json;
+	use v1::types::{Filter, LimitDirection, Log, H256 as RpcH256};
+	use super::{HashHistory, LogKey, ReorgDetector, limit_logs, append_limited_logs, limit_size, log_keys, test_filter};
+
+	fn logs(range: ::std::ops::Range<u64>) -> Vec<Log> {
+		range.map(|n| Log {
+			address: Default::default(),
+			topics: vec![],
+			data: Default::default(),
+			block_hash: None,
+			block_number: Some(n.into()),
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: "mined".into(),
+			removed: false,
+		}).collect()
+	}
+
+	#[test]
+	fn should_append_limited_logs_like_limit_logs() {
+		for &direction in &[LimitDirection::Last, LimitDirection::First] {
+			for &limit in &[None, Some(0), Some(1), Some(5), Some(10), Some(20)] {
+				for &(confirmed, pending) in &[(0, 0), (12, 0), (0, 12), (12, 3), (3, 12), (2, 2)] {
+					let confirmed = logs(0..confirmed);
+					let pending = logs(100..100 + pending);
+
+					let mut all = confirmed.clone();
+					all.extend(pending.clone());
+
+					assert_eq!(append_limited_logs(confirmed, pending, limit, direction), limit_logs(all, limit, direction));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn should_limit_size_from_either_end() {
+		let items = vec!["a".to_owned(), "bb".to_owned(), "ccc".to_owned()];
+		// `["a","bb","ccc"]` is 16 bytes long
+		assert_eq!(limit_size(items.clone(), None, LimitDirection::Last), (items.clone(), false));
+		assert_eq!(limit_size(items.clone(), Some(16), LimitDirection::First), (items.clone(), false));
+		assert_eq!(limit_size(items.clone(), Some(15), LimitDirection::First), (vec!["a".to_owned(), "bb".to_owned()], true));
+		assert_eq!(limit_size(items.clone(), Some(15), LimitDirection::Last), (vec!["bb".to_owned(), "ccc".to_owned()], true));
+		assert_eq!(limit_size(items.clone(), Some(7), LimitDirection::Last), (vec!["ccc".to_owned()], true));
+		assert_eq!(limit_size(items, Some(6), LimitDirection::Last), (vec![], true));
+	}
+
+	#[test]
+	fn should_limit_logs_in_canonical_order() {
+		let log = |block_number: Option<u64>, data: u8, removed: bool| {
+			let mut log = logs(0..1).remove(0);
+			log.block_number = block_number.map(Into::into);
+			log.log_index = block_number.map(|_| u64::from(data).into());
+			log.data = vec![data].into();
+			log.removed = removed;
+			log
+		};
+		let order = |logs: Vec<Log>| logs.into_iter()
+			.map(|log| (log.block_number.map(|n| Into::<U256>::into(n).low_u64()), log.data.into_vec()[0], log.removed))
+			.collect::<Vec<_>>();
+
+		let logs = vec![
+			log(None, 1, false),
+			log(Some(3), 0, false),
+			log(Some(2), 1, false),
+			log(None, 0, false),
+			log(Some(2), 0, false),
+			log(Some(4), 0, true),
+		];
+
+		assert_eq!(order(limit_logs(logs.clone(), None, LimitDirection::Last)), vec![
+			(Some(4), 0, true),
+			(Some(2), 0, false),
+			(Some(2), 1, false),
+			(Some(3), 0, false),
+			(None, 1, false),
+			(None, 0, false),
+		]);
+		// removed logs are kept whatever the limit
+		assert_eq!(order(limit_logs(logs.clone(), Some(3), LimitDirection::Last)), vec![
+			(Some(4), 0, true),
+			(Some(3), 0, false),
+			(None, 1, false),
+			(None, 0, false),
+		]);
+		assert_eq!(order(limit_logs(logs.clone(), Some(2), LimitDirection::First)), vec![
+			(Some(4), 0, true),
+			(Some(2), 0, false),
+			(Some(2), 1, false),
+		]);
+		assert_eq!(order(limit_logs(logs.clone(), Some(0), LimitDirection::Last)), vec![
+			(Some(4), 0, true),
+		]);
+		assert_eq!(
+			order(append_limited_logs(logs[..3].to_vec(), logs[3..].to_vec(), Some(3), LimitDirection::Last)),
+			order(limit_logs(logs, Some(3), LimitDirection::Last))
+		);
+	}
+
+	#[test]
+	fn should_limit_logs_from_either_end() {
+		let numbers = |logs: Vec<Log>| logs.into_iter()
+			.map(|log| Into::<U256>::into(log.block_number.unwrap()).low_u64())
+			.collect::<Vec<_>>();
+		let mut unordered = logs(0..6);
+		unordered.reverse();
+
+		assert_eq!(numbers(limit_logs(unordered.clone(), Some(2), LimitDirection::Last)), vec![4, 5]);
+		assert_eq!(numbers(limit_logs(unordered.clone(), Some(2), LimitDirection::First)), vec![0, 1]);
+		assert_eq!(numbers(limit_logs(unordered.clone(), Some(10), LimitDirection::First)), vec![0, 1, 2, 3, 4, 5]);
+		assert_eq!(numbers(limit_logs(unordered.clone(), None, LimitDirection::First)), vec![0, 1, 2, 3, 4, 5]);
+		assert_eq!(numbers(append_limited_logs(logs(3..6), logs(0..3), Some(2), LimitDirection::First)), vec![0, 1]);
+	}
+
+	#[test]
+	fn should_tell_apart_logs_of_the_same_transaction() {
+		let hash = |n: u64| -> Option<RpcH256> { Some(H256::from(n).into()) };
+		let mut logs = logs(0..3);
+		for log in &mut logs {
+			log.block_number = None;
+			log.transaction_hash = hash(1);
+		}
+		logs[2].transaction_hash = hash(2);
+
+		let expected: Vec<LogKey> = vec![
+			(None, None, hash(1), 0),
+			(None, None, hash(1), 1),
+			(None, None, hash(2), 0),
+		];
+		assert_eq!(log_keys(&logs), expected);
+	}
+
+	#[test]
+	fn should_detect_replaced_block_at_the_same_height() {
+		let mut detector = ReorgDetector::default();
+		assert_eq!(detector.reorged(|_| false), None);
+
+		detector.update(5, H256::from(5));
+		assert_eq!(detector.last_seen(), Some((5, H256::from(5))));
+		assert_eq!(detector.reorged(|hash| hash == H256::from(5)), None);
+
+		// replaced by another block or not known anymore
+		assert_eq!(detector.reorged(|hash| hash == H256::from(105)), Some(H256::from(5)));
+	}
+
+	#[test]
+	fn should_forget_oldest_hashes_first() {
+		let mut history = HashHistory::default();
+		history.extend((1..6).map(H256::from));
+		history.push(H256::from(1));
+		assert_eq!(history.len(), 5);
+
+		history.remove_all(&[H256::from(2), H256::from(7)]);
+		assert_eq!(history.len(), 4);
+		assert!(!history.contains(&H256::from(2)));
+
+		history.truncate_oldest(2);
+		assert!(!history.contains(&H256::from(1)));
+		assert!(!history.contains(&H256::from(3)));
+		assert!(history.contains(&H256::from(4)));
+		assert!(history.contains(&H256::from(5)));
+
+		history.truncate_oldest(0);
+		assert!(history.is_empty());
+	}
+
+	#[test]
+	fn should_test_whether_filter_matches_log() {
+		let filter = |s: &str| serde_json::from_str::<Filter>(s).unwrap();
+		let mut log = logs(5..6).remove(0);
+		log.address = H160::from(1).into();
+		log.topics = vec![H256::from(10).into(), H256::from(11).into()];
+		let address = format!("0x{:x}", H160::from(1));
+		let topic = |n: u64| format!("0x{:x}", H256::from(n));
+
+		// address match
+		assert!(test_filter(&filter(&format!(r#"{{"address":"{}"}}"#, address)), &log));
+		assert!(test_filter(&filter(&format!(r#"{{"address":["0x0000000000000000000000000000000000000002","{}"]}}"#, address)), &log));
+		assert!(!test_filter(&filter(r#"{"address":"0x0000000000000000000000000000000000000002"}"#), &log));
+
+		// topic wildcard
+		assert!(test_filter(&filter(&format!(r#"{{"topics":[null,"{}"]}}"#, topic(11))), &log));
+		assert!(test_filter(&filter(&format!(r#"{{"topics":[["{}","{}"]]}}"#, topic(12), topic(10))), &log));
+
+		// topic mismatch
+		assert!(!test_filter(&filter(&format!(r#"{{"topics":["{}"]}}"#, topic(11))), &log));
+		assert!(!test_filter(&filter(&format!(r#"{{"topics":[null,null,"{}"]}}"#, topic(11))), &log));
+		assert!(!test_filter(&filter(r#"{"topics":[[]]}"#), &log));
+
+		// block range
+		assert!(test_filter(&filter(r#"{"fromBlock":"0x5","toBlock":"0x5"}"#), &log));
+		assert!(test_filter(&filter(r#"{"fromBlock":"earliest","toBlock":"latest"}"#), &log));
+		assert!(!test_filter(&filter(r#"{"fromBlock":"0x6","toBlock":"latest"}"#), &log));
+		assert!(!test_filter(&filter(r#"{"fromBlock":"0x1","toBlock":"0x4"}"#), &log));
+		assert!(!test_filter(&filter(r#"{"toBlock":"earliest"}"#), &log));
+	}
+}
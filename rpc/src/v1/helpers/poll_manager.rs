@@ -16,74 +16,315 @@
 
 //! Indexes all rpc poll requests.
 
-use transient_hashmap::{TransientHashMap, Timer, StandardTimer};
+use std::{cmp, mem};
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use rand;
+use transient_hashmap::{Timer, StandardTimer};
+use v1::types::Filter;
 
-/// Lifetime of poll (in seconds).
-const POLL_LIFETIME: u32 = 60;
+/// Default lifetime of poll (in seconds).
+const POLL_LIFETIME: u64 = 300;
+/// Default maximal number of polls.
+pub const POLL_LIMIT: usize = 1024;
 
 pub type PollId = usize;
 
+/// Number of low bits of a poll id holding its sequence number.
+/// The remaining high bits hold the nonce of the allocator.
+const SEQUENCE_BITS: usize = mem::size_of::<PollId>() * 8 / 2;
+
+/// Defines possible errors when creating or polling a poll.
+#[derive(Debug, PartialEq)]
+pub enum PollError {
+	/// Maximal number of polls has been reached.
+	LimitReached,
+	/// Id allocator returned an id of a poll which is still installed.
+	IdInUse(PollId),
+	/// Changes of the poll were requested sooner than the minimal poll interval allows.
+	Throttled,
+}
+
+/// Allocates ids of new polls.
+pub trait PollIdAllocator {
+	/// Returns id for a new poll.
+	fn next_id(&mut self) -> PollId;
+}
+
+/// Allocates sequential ids, starting from zero.
+///
+/// A nonce may be embedded in the high bits of the ids, so that ids allocated
+/// by another allocator, e.g. before a restart, don't match polls installed since.
+#[derive(Debug, Default)]
+pub struct SequentialIds {
+	next_id: PollId,
+	nonce: PollId,
+}
+
+impl SequentialIds {
+	/// Creates new allocator embedding given nonce in the high bits of the ids.
+	pub fn with_nonce(nonce: PollId) -> Self {
+		SequentialIds {
+			next_id: 0,
+			nonce: nonce & (PollId::max_value() >> SEQUENCE_BITS),
+		}
+	}
+
+	/// Creates new allocator embedding a random, non-zero nonce in the ids.
+	pub fn with_random_nonce() -> Self {
+		SequentialIds::with_nonce(cmp::max(rand::random::<PollId>() >> SEQUENCE_BITS, 1))
+	}
+}
+
+impl PollIdAllocator for SequentialIds {
+	fn next_id(&mut self) -> PollId {
+		let sequence = self.next_id & (PollId::max_value() >> SEQUENCE_BITS);
+		self.next_id = self.next_id.wrapping_add(1);
+		(self.nonce << SEQUENCE_BITS) | sequence
+	}
+}
+
+/// Clock polls are evicted by, the system clock unless another timer is given,
+/// e.g. a mock timer in tests.
+pub struct PollTimer(Box<Timer + Send>);
+
+impl PollTimer {
+	/// Creates new clock reading the time from `timer`.
+	pub fn new<T: Timer + Send + 'static>(timer: T) -> Self {
+		PollTimer(Box::new(timer))
+	}
+}
+
+impl Default for PollTimer {
+	fn default() -> Self {
+		PollTimer::new(StandardTimer::default())
+	}
+}
+
+impl Timer for PollTimer {
+	fn get_time(&self) -> i64 {
+		self.0.get_time()
+	}
+}
+
+/// Stored poll together with the time it was last touched
+/// and the time its changes were last requested.
+struct Poll<F> {
+	filter: F,
+	last_polled: i64,
+	last_changes: Option<i64>,
+}
+
 /// Indexes all poll requests.
 ///
 /// Lazily garbage collects unused polls info.
-pub struct PollManager<F, T = StandardTimer> where T: Timer {
-	polls: TransientHashMap<PollId, F, T>,
-	next_available_id: PollId,
+/// Keeps an index of log filter criteria, so that identical filters can share them.
+pub struct PollManager<F, T = PollTimer, A = SequentialIds> where T: Timer, A: PollIdAllocator {
+	polls: HashMap<PollId, Poll<F>>,
+	shared_filters: HashMap<Filter, Weak<Filter>>,
+	ids: A,
+	timeout: i64,
+	limit: usize,
+	min_poll_interval: i64,
+	timer: T,
 }
 
-impl<F> PollManager<F, StandardTimer> {
+impl<F> PollManager<F, PollTimer> {
 	/// Creates new instance of indexer.
 	pub fn new() -> Self {
-		PollManager::new_with_timer(Default::default())
+		PollManager::new_with_timeout(Duration::from_secs(POLL_LIFETIME))
+	}
+
+	/// Creates new instance of indexer evicting polls idle for longer than `timeout`.
+	pub fn new_with_timeout(timeout: Duration) -> Self {
+		PollManager::new_with_timer_and_timeout(Default::default(), timeout)
+	}
+
+	/// Creates new instance of indexer holding at most `limit` polls at once.
+	pub fn new_with_limit(limit: usize) -> Self {
+		PollManager::new_with_limit_and_ids(limit, Default::default())
+	}
+
+	/// Creates new instance of indexer holding at most `limit` polls at once,
+	/// with ids of new polls allocated by `ids`.
+	pub fn new_with_limit_and_ids(limit: usize, ids: SequentialIds) -> Self {
+		PollManager {
+			limit: limit,
+			..PollManager::new_with_timer_and_ids(Default::default(), Duration::from_secs(POLL_LIFETIME), ids)
+		}
 	}
 }
 
 impl<F, T> PollManager<F, T> where T: Timer {
 
 	pub fn new_with_timer(timer: T) -> Self {
+		PollManager::new_with_timer_and_timeout(timer, Duration::from_secs(POLL_LIFETIME))
+	}
+
+	pub fn new_with_timer_and_timeout(timer: T, timeout: Duration) -> Self {
+		PollManager::new_with_timer_and_ids(timer, timeout, Default::default())
+	}
+}
+
+impl<F, T, A> PollManager<F, T, A> where T: Timer, A: PollIdAllocator {
+
+	/// Creates new instance of indexer with ids of new polls allocated by `ids`.
+	pub fn new_with_timer_and_ids(timer: T, timeout: Duration, ids: A) -> Self {
 		PollManager {
-			polls: TransientHashMap::new_with_timer(POLL_LIFETIME, timer),
-			next_available_id: 0,
+			polls: HashMap::new(),
+			shared_filters: HashMap::new(),
+			ids: ids,
+			timeout: timeout.as_secs() as i64,
+			limit: POLL_LIMIT,
+			min_poll_interval: 0,
+			timer: timer,
 		}
 	}
 
+	/// Replaces the clock polls are evicted by.
+	pub fn set_timer(&mut self, timer: T) {
+		self.timer = timer;
+	}
+
+	/// Sets the minimal interval between requests for changes of the same poll.
+	/// Changes requested sooner are rejected by `poll_changes`. There is no limit by default.
+	pub fn set_min_poll_interval(&mut self, interval: Duration) {
+		self.min_poll_interval = interval.as_secs() as i64;
+	}
+
+	/// Removes all polls which were not touched for longer than the timeout.
+	fn prune(&mut self) {
+		let now = self.timer.get_time();
+		let timeout = self.timeout;
+		self.polls.retain(|_, poll| now - poll.last_polled <= timeout);
+		self.forget_unshared_filters();
+	}
+
+	/// Removes criteria which are no longer held by any poll from the index.
+	fn forget_unshared_filters(&mut self) {
+		self.shared_filters.retain(|_, shared| shared.upgrade().is_some());
+	}
+
+	/// Returns criteria identical to `filter` which are still held by some poll,
+	/// or starts sharing `filter` if there are none.
+	pub fn shared_filter(&mut self, filter: Filter) -> Arc<Filter> {
+		if let Some(shared) = self.shared_filters.get(&filter).and_then(Weak::upgrade) {
+			return shared;
+		}
+
+		let shared = Arc::new(filter.clone());
+		self.shared_filters.insert(filter, Arc::downgrade(&shared));
+		shared
+	}
+
 	/// Returns id which can be used for new poll.
 	///
 	/// Stores information when last poll happend.
-	pub fn create_poll(&mut self, filter: F) -> PollId {
-		self.polls.prune();
+	/// Fails if the maximal number of polls is already installed
+	/// or the allocated id belongs to an installed poll.
+	pub fn create_poll(&mut self, filter: F) -> Result<PollId, PollError> {
+		self.prune();
+
+		if self.polls.len() >= self.limit {
+			return Err(PollError::LimitReached);
+		}
 
-		let id = self.next_available_id;
-		self.polls.insert(id, filter);
+		let id = self.ids.next_id();
+		if self.polls.contains_key(&id) {
+			return Err(PollError::IdInUse(id));
+		}
+
+		let last_polled = self.timer.get_time();
+		self.polls.insert(id, Poll { filter, last_polled, last_changes: None });
 
-		self.next_available_id += 1;
-		id
+		Ok(id)
 	}
 
 	// Implementation is always using `poll_mut`
 	/// Get a reference to stored poll filter
 	pub fn poll(&mut self, id: &PollId) -> Option<&F> {
-		self.polls.prune();
-		self.polls.get(id)
+		self.poll_mut(id).map(|filter| &*filter)
 	}
 
 	/// Get a mutable reference to stored poll filter
 	pub fn poll_mut(&mut self, id: &PollId) -> Option<&mut F> {
-		self.polls.prune();
-		self.polls.get_mut(id)
+		self.prune();
+
+		let now = self.timer.get_time();
+		self.polls.get_mut(id).map(|poll| {
+			poll.last_polled = now;
+			&mut poll.filter
+		})
 	}
 
-	/// Removes poll info.
-	pub fn remove_poll(&mut self, id: &PollId) {
-		self.polls.remove(id);
+	/// Get a reference to stored poll filter without touching it. Does not count as polling.
+	pub fn peek(&self, id: &PollId) -> Option<&F> {
+		let now = self.timer.get_time();
+		let timeout = self.timeout;
+		self.polls.get(id)
+			.and_then(|poll| if now - poll.last_polled <= timeout { Some(&poll.filter) } else { None })
+	}
+
+	/// Get a mutable reference to stored poll filter in order to return its changes.
+	/// Fails with `PollError::Throttled` if changes of the poll were requested
+	/// less than the minimal poll interval ago.
+	pub fn poll_changes(&mut self, id: &PollId) -> Result<Option<&mut F>, PollError> {
+		self.prune();
+
+		let now = self.timer.get_time();
+		let min_poll_interval = self.min_poll_interval;
+		let poll = match self.polls.get_mut(id) {
+			Some(poll) => poll,
+			None => return Ok(None),
+		};
+
+		if poll.last_changes.map_or(false, |last| now - last < min_poll_interval) {
+			return Err(PollError::Throttled);
+		}
+		poll.last_polled = now;
+		poll.last_changes = Some(now);
+		Ok(Some(&mut poll.filter))
+	}
+
+	/// Iterates over all live polls, yielding their ids, filters and number of seconds
+	/// since they were last polled. Does not count as polling.
+	pub fn iter<'a>(&'a self) -> Box<Iterator<Item = (PollId, &'a F, u64)> + 'a> {
+		let now = self.timer.get_time();
+		let timeout = self.timeout;
+		Box::new(self.polls.iter()
+			.filter(move |&(_, poll)| now - poll.last_polled <= timeout)
+			.map(move |(id, poll)| (*id, &poll.filter, (now - poll.last_polled) as u64)))
+	}
+
+	/// Removes poll info. Returns false if there was no such poll.
+	pub fn remove_poll(&mut self, id: &PollId) -> bool {
+		self.prune();
+		let removed = self.polls.remove(id).is_some();
+		self.forget_unshared_filters();
+		removed
+	}
+
+	/// Removes all polls and returns the number of polls removed. Expired polls are not counted.
+	pub fn remove_all_polls(&mut self) -> usize {
+		self.prune();
+		let removed = self.polls.len();
+		self.polls.clear();
+		self.forget_unshared_filters();
+		removed
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use std::cell::Cell;
+	use std::sync::Arc;
+	use std::time::Duration;
+	use serde_json;
 	use transient_hashmap::Timer;
 	use v1::helpers::PollManager;
+	use v1::types::Filter;
+	use super::{PollError, PollId, PollIdAllocator, SequentialIds};
 
 	struct TestTimer<'a> {
 		time: &'a Cell<i64>,
@@ -103,8 +344,8 @@ mod tests {
 		};
 
 		let mut indexer = PollManager::new_with_timer(timer);
-		assert_eq!(indexer.create_poll(20), 0);
-		assert_eq!(indexer.create_poll(20), 1);
+		assert_eq!(indexer.create_poll(20), Ok(0));
+		assert_eq!(indexer.create_poll(20), Ok(1));
 
 		time.set(10);
 		*indexer.poll_mut(&0).unwrap() = 21;
@@ -115,7 +356,7 @@ mod tests {
 		*indexer.poll_mut(&1).unwrap() = 23;
 		assert_eq!(*indexer.poll(&1).unwrap(), 23);
 
-		time.set(75);
+		time.set(315);
 		assert!(indexer.poll(&0).is_none());
 		assert_eq!(*indexer.poll(&1).unwrap(), 23);
 
@@ -123,4 +364,211 @@ mod tests {
 		assert!(indexer.poll(&1).is_none());
 	}
 
+	#[test]
+	fn should_throttle_changes_requested_too_often() {
+		let time = Cell::new(0);
+		let timer = TestTimer {
+			time: &time,
+		};
+
+		let mut indexer = PollManager::new_with_timer(timer);
+		indexer.create_poll(20).unwrap();
+		assert_eq!(indexer.poll_changes(&0), Ok(Some(&mut 20)));
+		assert_eq!(indexer.poll_changes(&0), Ok(Some(&mut 20)));
+
+		indexer.set_min_poll_interval(Duration::from_secs(5));
+		time.set(10);
+		assert_eq!(indexer.poll_changes(&0), Ok(Some(&mut 20)));
+		time.set(14);
+		assert_eq!(indexer.poll_changes(&0), Err(PollError::Throttled));
+		// other accesses are not limited
+		assert_eq!(indexer.poll_mut(&0), Some(&mut 20));
+		time.set(15);
+		assert_eq!(indexer.poll_changes(&0), Ok(Some(&mut 20)));
+		assert_eq!(indexer.poll_changes(&1), Ok(None));
+	}
+
+	#[test]
+	fn should_iterate_over_live_polls() {
+		let time = Cell::new(0);
+		let timer = TestTimer {
+			time: &time,
+		};
+
+		let mut indexer = PollManager::new_with_timer(timer);
+		indexer.create_poll(20).unwrap();
+		time.set(30);
+		indexer.create_poll(21).unwrap();
+
+		time.set(310);
+		assert_eq!(indexer.iter().collect::<Vec<_>>(), vec![(1, &21, 280)]);
+		// iterating doesn't count as polling
+		time.set(340);
+		assert!(indexer.iter().next().is_none());
+	}
+
+	#[test]
+	fn should_peek_at_live_polls() {
+		let time = Cell::new(0);
+		let timer = TestTimer {
+			time: &time,
+		};
+
+		let mut indexer = PollManager::new_with_timer(timer);
+		indexer.create_poll(20).unwrap();
+
+		time.set(290);
+		assert_eq!(indexer.peek(&0), Some(&20));
+		assert!(indexer.peek(&1).is_none());
+		// peeking doesn't count as polling
+		time.set(310);
+		assert!(indexer.peek(&0).is_none());
+	}
+
+	#[test]
+	fn should_remove_all_polls() {
+		let time = Cell::new(0);
+		let timer = TestTimer {
+			time: &time,
+		};
+
+		let mut indexer = PollManager::new_with_timer(timer);
+		indexer.create_poll(20).unwrap();
+		indexer.create_poll(21).unwrap();
+		time.set(30);
+		indexer.create_poll(22).unwrap();
+
+		// the first two polls have expired
+		time.set(310);
+		assert_eq!(indexer.remove_all_polls(), 1);
+		assert!(indexer.poll(&2).is_none());
+		assert_eq!(indexer.create_poll(23), Ok(3));
+	}
+
+	#[test]
+	fn should_evict_polls_after_custom_timeout() {
+		let time = Cell::new(0);
+		let timer = TestTimer {
+			time: &time,
+		};
+
+		let mut indexer = PollManager::new_with_timer_and_timeout(timer, Duration::from_secs(5));
+		assert_eq!(indexer.create_poll(20), Ok(0));
+		assert_eq!(indexer.create_poll(30), Ok(1));
+
+		time.set(4);
+		assert_eq!(*indexer.poll(&0).unwrap(), 20);
+
+		time.set(8);
+		assert_eq!(*indexer.poll(&0).unwrap(), 20);
+		assert!(indexer.poll(&1).is_none());
+
+		time.set(20);
+		assert!(indexer.poll_mut(&0).is_none());
+	}
+
+	#[test]
+	fn should_not_panic_when_pruning_empty_indexer() {
+		let time = Cell::new(0);
+		let timer = TestTimer {
+			time: &time,
+		};
+
+		let mut indexer = PollManager::<usize, _>::new_with_timer_and_timeout(timer, Duration::from_secs(1));
+		time.set(100);
+		assert!(indexer.poll(&0).is_none());
+		indexer.remove_poll(&0);
+		assert_eq!(indexer.create_poll(1), Ok(0));
+	}
+
+	#[test]
+	fn should_reject_polls_over_limit() {
+		let mut indexer = PollManager::new_with_limit(2);
+		assert_eq!(indexer.create_poll(20), Ok(0));
+		assert_eq!(indexer.create_poll(20), Ok(1));
+		assert_eq!(indexer.create_poll(20), Err(PollError::LimitReached));
+
+		indexer.remove_poll(&0);
+		assert_eq!(indexer.create_poll(20), Ok(2));
+		assert_eq!(indexer.create_poll(20), Err(PollError::LimitReached));
+	}
+
+	#[test]
+	fn should_use_custom_id_allocator() {
+		struct FixedIds(Vec<PollId>);
+
+		impl PollIdAllocator for FixedIds {
+			fn next_id(&mut self) -> PollId {
+				self.0.remove(0)
+			}
+		}
+
+		let time = Cell::new(0);
+		let timer = TestTimer {
+			time: &time,
+		};
+
+		let ids = FixedIds(vec![0xdead, 0xbeef, 0xdead]);
+		let mut indexer = PollManager::new_with_timer_and_ids(timer, Duration::from_secs(60), ids);
+		assert_eq!(indexer.create_poll(20), Ok(0xdead));
+		assert_eq!(indexer.create_poll(21), Ok(0xbeef));
+		assert_eq!(indexer.create_poll(22), Err(PollError::IdInUse(0xdead)));
+
+		*indexer.poll_mut(&0xbeef).unwrap() = 23;
+		assert_eq!(*indexer.poll(&0xdead).unwrap(), 20);
+		assert_eq!(*indexer.poll(&0xbeef).unwrap(), 23);
+	}
+
+	#[test]
+	fn should_not_match_ids_allocated_with_another_nonce() {
+		let time = Cell::new(0);
+		let timer = || TestTimer {
+			time: &time,
+		};
+
+		// e.g. the same manager before and after a restart
+		let mut before = PollManager::new_with_timer_and_ids(timer(), Duration::from_secs(60), SequentialIds::with_nonce(1));
+		let mut after = PollManager::new_with_timer_and_ids(timer(), Duration::from_secs(60), SequentialIds::with_nonce(2));
+
+		let stale_id = before.create_poll(20).unwrap();
+		let id = after.create_poll(21).unwrap();
+
+		assert!(id != stale_id);
+		assert!(after.poll(&stale_id).is_none());
+		assert_eq!(*after.poll(&id).unwrap(), 21);
+
+		// the sequence number still starts from zero
+		let sequence_mask = PollId::max_value() >> super::SEQUENCE_BITS;
+		assert_eq!(stale_id & sequence_mask, 0);
+		assert_eq!(id & sequence_mask, 0);
+	}
+
+	#[test]
+	fn should_share_identical_filters_while_held() {
+		let mut indexer = PollManager::new();
+		let filter: Filter = serde_json::from_str(r#"{"fromBlock":"0x5"}"#).unwrap();
+		let other: Filter = serde_json::from_str(r#"{"fromBlock":"0x6"}"#).unwrap();
+
+		let first = indexer.shared_filter(filter.clone());
+		assert!(Arc::ptr_eq(&first, &indexer.shared_filter(filter.clone())));
+		assert!(!Arc::ptr_eq(&first, &indexer.shared_filter(other)));
+		assert_eq!(indexer.create_poll(first), Ok(0));
+
+		// criteria are forgotten once the last poll holding them is removed
+		assert!(indexer.remove_poll(&0));
+		assert!(indexer.shared_filters.is_empty());
+		assert_eq!(*indexer.shared_filter(filter.clone()), filter);
+	}
+
+	#[test]
+	fn should_embed_random_nonce_in_ids() {
+		let mut ids = SequentialIds::with_random_nonce();
+		let first = ids.next_id();
+		assert!(first != 0);
+		assert_eq!(ids.next_id(), first + 1);
+
+		let mut sequential = SequentialIds::default();
+		assert_eq!(sequential.next_id(), 0);
+		assert_eq!(sequential.next_id(), 1);
+	}
 }
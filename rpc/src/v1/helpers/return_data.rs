@@ -0,0 +1,185 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction return data helpers.
+
+use std::io::Write;
+use base64;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use rustc_hex::{FromHex, ToHex};
+use ethcore::client::{BlockChainClient, BlockId, CallAnalytics};
+use ethcore::executed::CallError;
+use ethereum_types::H256;
+use v1::types::ReplayedReceipt;
+use v1::types::pubsub::{ReturnData, ReturnDataEncoding};
+
+const ANALYTICS: CallAnalytics = CallAnalytics { transaction_tracing: false, vm_tracing: false, state_diffing: false, };
+
+/// Replays transactions of the given block in a single pass and returns the data returned
+/// by the execution of those with given indices and hashes, which have to be in block order.
+/// Transactions after the last given one are not replayed.
+pub fn replay_block<C: BlockChainClient>(client: &C, id: BlockId, transactions: Vec<(usize, H256)>, removed: bool) -> Result<Vec<ReturnData>, CallError> {
+	if transactions.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	let mut executed = client.replay_block_transactions(id, ANALYTICS)?.enumerate();
+	transactions.into_iter()
+		.map(|(index, hash)| {
+			let output = executed.by_ref()
+				.find(|&(replayed, _)| replayed == index)
+				.map(|(_, executed)| executed.output)
+				.ok_or(CallError::TransactionNotFound)?;
+			Ok(ReturnData {
+				transaction_hash: hash,
+				transaction_index: Some((index as u64).into()),
+				return_data: output.to_hex(),
+				removed: removed,
+				compressed: false,
+				encoding: ReturnDataEncoding::Hex,
+			})
+		})
+		.collect()
+}
+
+/// Replays all transactions of the given block in a single pass and returns the gas used
+/// and the data returned by their execution. `hashes` are the hashes of all block transactions.
+pub fn replay_block_receipts<C: BlockChainClient>(client: &C, id: BlockId, hashes: Vec<H256>) -> Result<Vec<ReplayedReceipt>, CallError> {
+	if hashes.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	let executed = client.replay_block_transactions(id, ANALYTICS)?;
+	let receipts = hashes.into_iter()
+		.zip(executed)
+		.enumerate()
+		.map(|(index, (hash, executed))| ReplayedReceipt {
+			transaction_hash: hash.into(),
+			transaction_index: (index as u64).into(),
+			gas_used: executed.gas_used.into(),
+			output: executed.output.into(),
+		})
+		.collect();
+	Ok(receipts)
+}
+
+/// Decodes the output of uncompressed return data.
+fn decode(data: &ReturnData) -> Vec<u8> {
+	match data.encoding {
+		ReturnDataEncoding::Hex => data.return_data.from_hex().expect("hex return data is always valid hex; qed"),
+		ReturnDataEncoding::Base64 => base64::decode(&data.return_data).expect("base64 return data is always valid base64; qed"),
+	}
+}
+
+/// Returns given return data with its output in given encoding.
+/// Compressed output is always base64-encoded and is returned unchanged.
+pub fn encode(data: ReturnData, encoding: ReturnDataEncoding) -> ReturnData {
+	if data.compressed || data.encoding == encoding {
+		return data;
+	}
+
+	let output = decode(&data);
+	ReturnData {
+		return_data: match encoding {
+			ReturnDataEncoding::Hex => output.to_hex(),
+			ReturnDataEncoding::Base64 => base64::encode(&output),
+		},
+		encoding: encoding,
+		..data
+	}
+}
+
+/// Returns given return data with its output gzip-compressed and base64-encoded.
+pub fn compress(data: ReturnData) -> ReturnData {
+	if data.compressed {
+		return data;
+	}
+
+	let output = decode(&data);
+	let mut encoder = GzEncoder::new(Vec::new(), Compression::Default);
+	encoder.write_all(&output).expect("writing to a vector never fails; qed");
+	let compressed = encoder.finish().expect("writing to a vector never fails; qed");
+
+	ReturnData {
+		return_data: base64::encode(&compressed),
+		compressed: true,
+		encoding: ReturnDataEncoding::Base64,
+		..data
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::Read;
+	use base64;
+	use flate2::read::GzDecoder;
+	use rustc_hex::{FromHex, ToHex};
+	use v1::types::pubsub::{ReturnData, ReturnDataEncoding, ReturnDataOptions};
+	use super::{compress, encode};
+
+	fn decompress(data: &str) -> Vec<u8> {
+		let compressed = base64::decode(data).unwrap();
+		let mut output = Vec::new();
+		GzDecoder::new(&compressed[..]).unwrap().read_to_end(&mut output).unwrap();
+		output
+	}
+
+	#[test]
+	fn should_round_trip_compressed_return_data() {
+		let output: Vec<u8> = (0..64 * 1024).map(|i| (i % 7) as u8).collect();
+		let data = ReturnData {
+			transaction_hash: 5.into(),
+			transaction_index: Some(1u64.into()),
+			return_data: output.to_hex(),
+			removed: false,
+			compressed: false,
+			encoding: ReturnDataEncoding::Hex,
+		};
+
+		let compressed = compress(data.clone());
+		assert!(compressed.compressed);
+		assert_eq!(compressed.encoding, ReturnDataEncoding::Base64);
+		assert!(compressed.return_data.len() < data.return_data.len() / 10);
+		assert_eq!(compressed.transaction_hash, data.transaction_hash);
+		assert_eq!(decompress(&compressed.return_data), output);
+		assert_eq!(compress(compressed.clone()), compressed);
+	}
+
+	#[test]
+	fn should_round_trip_return_data_in_either_encoding() {
+		let output: Vec<u8> = (0..1024).map(|i| (i % 251) as u8).collect();
+		let data = ReturnData {
+			transaction_hash: 5.into(),
+			transaction_index: Some(1u64.into()),
+			return_data: output.to_hex(),
+			removed: false,
+			compressed: false,
+			encoding: ReturnDataOptions::default().encoding,
+		};
+		assert_eq!(data.encoding, ReturnDataEncoding::Hex);
+
+		let base64 = encode(data.clone(), ReturnDataEncoding::Base64);
+		assert_eq!(base64.encoding, ReturnDataEncoding::Base64);
+		assert!(base64.return_data.len() < data.return_data.len());
+		assert_eq!(base64::decode(&base64.return_data).unwrap(), output);
+
+		let hex = encode(base64.clone(), ReturnDataEncoding::Hex);
+		assert_eq!(hex, data);
+		assert_eq!(hex.return_data.from_hex().unwrap(), output);
+		assert_eq!(decompress(&compress(base64).return_data), output);
+	}
+}
@@ -43,7 +43,7 @@ use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::future;
 use jsonrpc_macros::Trailing;
 
-use v1::helpers::{errors, limit_logs, fake_sign};
+use v1::helpers::{errors, limit_logs, append_limited_logs, fake_sign};
 use v1::helpers::dispatch::{FullDispatcher, default_gas_price};
 use v1::helpers::block_import::is_major_importing;
 use v1::helpers::accounts::unwrap_provider;
@@ -51,7 +51,7 @@ use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
 	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
-	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256, block_number_to_id,
+	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U64 as RpcU64, U256 as RpcU256, block_number_to_id,
 };
 use v1::metadata::Metadata;
 
@@ -208,6 +208,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> EthClient<C, SN, S
 					BlockNumber::Earliest => BlockId::Earliest,
 					BlockNumber::Num(n) => BlockId::Number(n),
 					BlockNumber::Pending => unreachable!(), // Already covered
+					BlockNumber::Safe | BlockNumber::Finalized => return Err(errors::unsupported_block_tag()),
 				};
 
 				client_query(id)
@@ -396,6 +397,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> EthClient<C, SN, S
 			BlockNumber::Num(num) => BlockId::Number(num).into(),
 			BlockNumber::Earliest => BlockId::Earliest.into(),
 			BlockNumber::Latest => BlockId::Latest.into(),
+			BlockNumber::Safe | BlockNumber::Finalized => unreachable!(), // Rejected by `check_known`
 
 			BlockNumber::Pending => {
 				let info = self.client.chain_info();
@@ -434,6 +436,7 @@ fn check_known<C>(client: &C, number: BlockNumber) -> Result<()> where C: Mining
 
 	let id = match number {
 		BlockNumber::Pending => return Ok(()),
+		BlockNumber::Safe | BlockNumber::Finalized => return Err(errors::unsupported_block_tag()),
 
 		BlockNumber::Num(n) => BlockId::Number(n),
 		BlockNumber::Latest => BlockId::Latest,
@@ -462,6 +465,10 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		Ok(format!("{}", version))
 	}
 
+	fn chain_id(&self) -> Result<Option<RpcU64>> {
+		Ok(self.client.signing_chain_id().map(RpcU64::from))
+	}
+
 	fn syncing(&self) -> Result<SyncStatus> {
 		use ethcore::snapshot::RestorationStatus;
 
@@ -590,7 +597,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 
 			number => {
 				try_bf!(check_known(&*self.client, number.clone()));
-				match self.client.nonce(&address, block_number_to_id(number)) {
+				match self.client.nonce(&address, try_bf!(block_number_to_id(number))) {
 					Some(nonce) => Ok(nonce.into()),
 					None => Err(errors::state_pruned()),
 				}
@@ -611,7 +618,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				self.miner.status().transactions_in_pending_block.into()
 			),
 			_ =>
-				self.client.block(block_number_to_id(num))
+				self.client.block(try_bf!(block_number_to_id(num)))
 					.map(|block| block.transactions_count().into())
 		}))
 	}
@@ -624,7 +631,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 	fn block_uncles_count_by_number(&self, num: BlockNumber) -> BoxFuture<Option<RpcU256>> {
 		Box::new(future::ok(match num {
 			BlockNumber::Pending => Some(0.into()),
-			_ => self.client.block(block_number_to_id(num))
+			_ => self.client.block(try_bf!(block_number_to_id(num)))
 					.map(|block| block.uncles_count().into()
 			),
 		}))
@@ -674,6 +681,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 			BlockNumber::Earliest => PendingOrBlock::Block(BlockId::Earliest),
 			BlockNumber::Num(num) => PendingOrBlock::Block(BlockId::Number(num)),
 			BlockNumber::Pending => PendingOrBlock::Pending,
+			BlockNumber::Safe | BlockNumber::Finalized => return Box::new(future::err(errors::unsupported_block_tag())),
 		};
 
 		let transaction_id = PendingTransactionId::Location(block_id, index.value());
@@ -707,6 +715,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 			BlockNumber::Num(num) => PendingUncleId { id: PendingOrBlock::Block(BlockId::Number(num)), position: index.value() },
 
 			BlockNumber::Pending => PendingUncleId { id: PendingOrBlock::Pending, position: index.value() },
+			BlockNumber::Safe | BlockNumber::Finalized => return Box::new(future::err(errors::unsupported_block_tag())),
 		};
 
 		Box::new(future::done(self.uncle(id)))
@@ -716,9 +725,49 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		Err(errors::deprecated("Compilation functionality is deprecated.".to_string()))
 	}
 
-	fn logs(&self, filter: Filter) -> BoxFuture<Vec<Log>> {
-		let include_pending = filter.to_block == Some(BlockNumber::Pending);
-		let filter: EthcoreFilter = filter.into();
+	fn logs(&self, mut filter: Filter) -> BoxFuture<Vec<Log>> {
+		if let Err(err) = filter.validate() {
+			return Box::new(future::err(err));
+		}
+
+		let include_pending = filter.includes_pending();
+		let (limit, direction) = (filter.limit, filter.limit_direction());
+		let transaction_hash = filter.transaction_hash.take();
+		let block_hashes = filter.block_hashes.take();
+		let filter = try_bf!(filter.try_into_eth_filter());
+
+		// logs of a single transaction are taken from its receipt
+		if let Some(hash) = transaction_hash {
+			let logs = self.client.transaction_receipt(TransactionId::Hash(hash.into()))
+				.map_or_else(Vec::new, |receipt| receipt.logs.into_iter()
+					.filter(|log| filter.matches(&log.entry))
+					.map(From::from)
+					.collect());
+			return Box::new(future::ok(limit_logs(logs, limit, direction)));
+		}
+
+		// logs of a set of blocks are merged from the logs of each canonical one
+		if let Some(mut hashes) = block_hashes {
+			hashes.sort();
+			hashes.dedup();
+
+			let mut logs = Vec::new();
+			for hash in hashes.into_iter().map(Into::<H256>::into) {
+				let canonical = self.client.block_header(BlockId::Hash(hash))
+					.map_or(false, |header| self.client.block_hash(BlockId::Number(header.number())) == Some(hash));
+				if !canonical {
+					warn!(target: "rpc", "Skipping logs of unknown or non-canonical block {:?}", hash);
+					continue;
+				}
+
+				let mut block_filter = filter.clone();
+				block_filter.from_block = BlockId::Hash(hash);
+				block_filter.to_block = BlockId::Hash(hash);
+				logs.extend(self.client.logs(block_filter).into_iter().map(Log::from));
+			}
+			return Box::new(future::ok(limit_logs(logs, limit, direction)));
+		}
+
 		let mut logs = self.client.logs(filter.clone())
 			.into_iter()
 			.map(From::from)
@@ -727,11 +776,11 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		if include_pending {
 			let best_block = self.client.chain_info().best_block_number;
 			let pending = pending_logs(&*self.miner, best_block, &filter);
-			logs.extend(pending);
+			logs = append_limited_logs(logs, pending, limit, direction);
+		} else {
+			logs = limit_logs(logs, limit, direction);
 		}
 
-		let logs = limit_logs(logs, filter.limit);
-
 		Box::new(future::ok(logs))
 	}
 
@@ -846,6 +895,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				BlockNumber::Earliest => BlockId::Earliest,
 				BlockNumber::Latest => BlockId::Latest,
 				BlockNumber::Pending => unreachable!(), // Already covered
+				BlockNumber::Safe | BlockNumber::Finalized => return Box::new(future::err(errors::unsupported_block_tag())),
 			};
 
 			let state = try_bf!(self.client.state_at(id).ok_or(errors::state_pruned()));
@@ -879,6 +929,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 				BlockNumber::Earliest => BlockId::Earliest,
 				BlockNumber::Latest => BlockId::Latest,
 				BlockNumber::Pending => unreachable!(), // Already covered
+				BlockNumber::Safe | BlockNumber::Finalized => return Box::new(future::err(errors::unsupported_block_tag())),
 			};
 
 			let state = try_bf!(self.client.state_at(id).ok_or(errors::state_pruned()));
@@ -16,42 +16,233 @@
 
 //! Eth Filter RPC implementation
 
+use std::{cmp, fmt};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::sync::Arc;
-use std::collections::HashSet;
+use std::time::Duration;
 
 use ethcore::miner::MinerService;
 use ethcore::filter::Filter as EthcoreFilter;
-use ethcore::client::{BlockChainClient, BlockId};
-use ethereum_types::H256;
+use ethcore::client::{BlockChainClient, BlockId, TransactionId};
+use ethereum_types::{H256, U256 as EthU256};
 use parking_lot::Mutex;
+use tokio_timer::{Interval, Timer};
 
-use jsonrpc_core::{BoxFuture, Result};
-use jsonrpc_core::futures::{future, Future};
+use jsonrpc_core::{BoxFuture, Error, Result};
+use jsonrpc_core::futures::{future, stream, Async, Future, Poll, Stream};
 use jsonrpc_core::futures::future::Either;
 use v1::traits::EthFilter;
-use v1::types::{BlockNumber, Index, Filter, FilterChanges, Log, H256 as RpcH256, U256 as RpcU256};
-use v1::helpers::{PollFilter, PollManager, limit_logs};
+use jsonrpc_macros::Trailing;
+use v1::types::{BatchFilterChanges, BlockNumber, Index, Filter, FilterChanges, FilterInfo, FilterLogs, FilterState, FilterStats, LimitDirection, Log, PendingTransactionFilterOptions, PendingTransactionHash, RichHeader, Transaction, H256 as RpcH256, U256 as RpcU256};
+use v1::helpers::{errors, HashHistory, PollFilter, SyncPollFilter, SharedFilters, PollManager, PollError, FILTER_KINDS, SequentialIds, POLL_LIMIT, limit_logs, append_limited_logs, limit_size, log_keys, test_filter};
 use v1::impls::eth::pending_logs;
 
+/// Number of recently reported blocks remembered by block filters to detect reorganisations.
+const BLOCK_FILTER_HISTORY: usize = 64;
+
+/// Number of recently mined transactions remembered by pending transaction filters
+/// to detect transactions returned to the pool by a reorganisation.
+const MINED_TRANSACTIONS_HISTORY: usize = 1024;
+
 /// Something which provides data that can be filtered over.
 pub trait Filterable {
 	/// Current best block number.
 	fn best_block_number(&self) -> u64;
 
+	/// Current best block hash.
+	fn best_block_hash(&self) -> H256;
+
 	/// Get a block hash by block id.
 	fn block_hash(&self, id: BlockId) -> Option<RpcH256>;
 
+	/// Checks whether the block with given hash is on the canonical chain.
+	/// Used by all checks for chain reorganisations.
+	fn is_canonical(&self, block_hash: RpcH256) -> bool;
+
+	/// Get a block header by block id.
+	fn block_header(&self, id: BlockId) -> Option<RichHeader>;
+
+	/// Number of the first block with a timestamp not lower than the given one,
+	/// `None` if all blocks are older.
+	fn first_block_since(&self, timestamp: u64) -> Option<u64>;
+
 	/// pending transaction hashes at the given block.
 	fn pending_transactions_hashes(&self, block_number: u64) -> Vec<H256>;
 
+	/// pending transaction hashes at the given block, the most recently queued first.
+	fn recent_pending_transactions_hashes(&self, block_number: u64) -> Vec<H256> {
+		self.pending_transactions_hashes(block_number)
+	}
+
+	/// Whether a transaction with the given hash is included in a canonical block.
+	fn is_mined_transaction(&self, _hash: H256) -> bool {
+		false
+	}
+
+	/// Get a pending transaction by its hash.
+	/// The sender is the one recovered when the transaction entered the pool.
+	fn pending_transaction(&self, hash: H256) -> Option<Transaction>;
+
 	/// Get logs that match the given filter.
 	fn logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>>;
 
-	/// Get logs from the pending block.
-	fn pending_logs(&self, block_number: u64, filter: &EthcoreFilter) -> Vec<Log>;
+	/// Get logs that match the given filter which doesn't restrict addresses.
+	/// Backends able to look logs up by topics should override it, defaults to `logs`.
+	fn topic_logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>> {
+		self.logs(filter)
+	}
+
+	/// Get logs that match the given filter, limited to `limit` logs from the end given by `direction`,
+	/// together with the number of all matching logs.
+	/// Backends able to count matches without retrieving them all should override it.
+	fn logs_with_count(&self, filter: EthcoreFilter, limit: Option<usize>, direction: LimitDirection) -> BoxFuture<(Vec<Log>, usize)> where
+		Self: Sized,
+	{
+		Box::new(fetch_logs(self, filter).map(move |logs| {
+			let count = logs.len();
+			(limit_logs(logs, limit, direction), count)
+		}))
+	}
+
+	/// Get logs that match the given filter as a stream, in the order of blocks.
+	/// Logs are yielded as the backend produces them, chunk by chunk if `logs_chunk_size` is set.
+	/// Only the last logs kept by the filter's limit are buffered.
+	fn logs_stream(&self, filter: EthcoreFilter) -> BoxStream<Log> where
+		Self: Sized,
+	{
+		stream_logs(self, filter)
+	}
+
+	/// Number of the latest block which is unlikely to be reorged.
+	/// Backends which don't track it reject `safe` filter bounds.
+	fn safe_block_number(&self) -> Option<u64> {
+		None
+	}
+
+	/// Number of the latest block which can't be reorged anymore.
+	/// Backends which don't track finality reject `finalized` filter bounds.
+	fn finalized_block_number(&self) -> Option<u64> {
+		None
+	}
+
+	/// Get logs from up to `depth` pending blocks, the one to be mined next first.
+	/// Backends with a single pending block return its logs whatever the depth.
+	fn pending_logs(&self, block_number: u64, depth: usize, filter: &EthcoreFilter) -> Vec<Log>;
+
+	/// Whether there are pending blocks to take pending logs from.
+	/// Filters reaching `pending` don't include pending logs otherwise.
+	fn has_pending_source(&self) -> bool;
+
+	/// Get logs matching the filter from blocks between the given block and the canonical chain
+	/// (excluding the canonical ancestor), marked as removed. Also returns the number of
+	/// non-canonical blocks traversed.
+	fn removed_logs(&self, block_hash: H256, filter: &EthcoreFilter) -> (Vec<Log>, u64);
 
 	/// Get a reference to the poll manager.
-	fn polls(&self) -> &Mutex<PollManager<PollFilter>>;
+	/// It's only locked to look filters up, each filter is locked on its own while polled.
+	fn polls(&self) -> &SharedFilters;
+
+	/// Maximal number of blocks covered by a single `logs` call in `filter_logs`.
+	/// `None` fetches the whole range at once.
+	fn logs_chunk_size(&self) -> Option<u64>;
+
+	/// Maximal span of blocks log filters are allowed to cover.
+	fn max_block_range(&self) -> Option<u64>;
+
+	/// Maximal serialized size of logs returned by a single request, in bytes.
+	fn max_result_size(&self) -> Option<usize>;
+
+	/// Maximal number of blocks beyond the best block new log filters may start at.
+	fn max_future_blocks(&self) -> Option<u64>;
+
+	/// Number of pending blocks whose logs are included in pending logs.
+	fn pending_logs_depth(&self) -> usize;
+
+	/// Number of reported pending transactions remembered after they leave the pool.
+	fn pending_transactions_history(&self) -> Option<usize>;
+
+	/// Maximal time logs of a filter may take to fetch.
+	fn logs_timeout(&self) -> Option<&LogsTimeout>;
+}
+
+/// Deadline for fetching logs.
+#[derive(Clone)]
+pub struct LogsTimeout {
+	timer: Timer,
+	duration: Duration,
+}
+
+impl LogsTimeout {
+	/// Creates new `LogsTimeout` failing requests which take longer than `duration`.
+	pub fn new(duration: Duration) -> Self {
+		LogsTimeout {
+			timer: Timer::default(),
+			duration: duration,
+		}
+	}
+}
+
+/// Eth filter client options.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EthFilterOptions {
+	/// Maximal number of filters installed at the same time.
+	pub poll_limit: usize,
+	/// Maximal number of blocks fetched by a single logs request in `eth_getFilterLogs`.
+	/// Wider ranges are split into chunks which are fetched concurrently.
+	pub logs_chunk_size: Option<u64>,
+	/// Maximal span of blocks log filters are allowed to cover.
+	pub max_block_range: Option<u64>,
+	/// Maximal number of blocks beyond the best block new log filters may start at,
+	/// leaving some slack for blocks imported while the request is processed.
+	/// `None` accepts filters starting at any block.
+	pub max_future_blocks: Option<u64>,
+	/// Maximal serialized size of logs returned by `eth_getFilterLogs` and the `parity_` variants
+	/// telling the range of blocks scanned, in bytes. Logs which don't fit are left out,
+	/// which the `parity_` variants report as `truncated`. `None` doesn't limit the size.
+	pub max_result_size: Option<usize>,
+	/// Number of pending blocks logs are included from when filters ask for pending logs,
+	/// for backends which propose more than the block to be mined next.
+	pub pending_logs_depth: usize,
+	/// Number of reported pending transactions remembered after they leave the pool,
+	/// so that they are not reported again if they are re-added shortly after.
+	/// `None` reports re-added transactions as new.
+	pub pending_transactions_history: Option<usize>,
+	/// Maximal time fetching logs of a filter may take before the request fails.
+	/// `None` waits indefinitely.
+	pub logs_timeout: Option<Duration>,
+	/// Embed a random nonce in the ids of new filters, so that ids handed out
+	/// before a restart are not mistaken for filters installed after it.
+	pub filter_id_nonce: bool,
+	/// Minimal interval between requests for changes of the same filter,
+	/// requests arriving sooner are rejected. `None` doesn't limit polling.
+	pub min_poll_interval: Option<Duration>,
+}
+
+impl EthFilterOptions {
+	/// Creates new default `EthFilterOptions` and allows alterations
+	/// by provided function.
+	pub fn with<F: Fn(&mut Self)>(fun: F) -> Self {
+		let mut options = Self::default();
+		fun(&mut options);
+		options
+	}
+}
+
+impl Default for EthFilterOptions {
+	fn default() -> Self {
+		EthFilterOptions {
+			poll_limit: POLL_LIMIT,
+			logs_chunk_size: None,
+			max_block_range: None,
+			max_future_blocks: None,
+			max_result_size: None,
+			pending_logs_depth: 1,
+			pending_transactions_history: None,
+			logs_timeout: None,
+			filter_id_nonce: false,
+			min_poll_interval: None,
+		}
+	}
 }
 
 /// Eth filter rpc implementation for a full node.
@@ -61,18 +252,50 @@ pub struct EthFilterClient<C, M> where
 
 	client: Arc<C>,
 	miner: Arc<M>,
-	polls: Mutex<PollManager<PollFilter>>,
+	polls: SharedFilters,
+	logs_chunk_size: Option<u64>,
+	max_block_range: Option<u64>,
+	max_future_blocks: Option<u64>,
+	max_result_size: Option<usize>,
+	pending_logs_depth: usize,
+	pending_transactions_history: Option<usize>,
+	logs_timeout: Option<LogsTimeout>,
 }
 
 impl<C, M> EthFilterClient<C, M> where C: BlockChainClient, M: MinerService {
 	/// Creates new Eth filter client.
 	pub fn new(client: Arc<C>, miner: Arc<M>) -> Self {
+		EthFilterClient::new_with_options(client, miner, Default::default())
+	}
+
+	/// Creates new Eth filter client with given options.
+	pub fn new_with_options(client: Arc<C>, miner: Arc<M>, options: EthFilterOptions) -> Self {
+		let ids = match options.filter_id_nonce {
+			true => SequentialIds::with_random_nonce(),
+			false => SequentialIds::default(),
+		};
+		let mut polls = PollManager::new_with_limit_and_ids(options.poll_limit, ids);
+		if let Some(interval) = options.min_poll_interval {
+			polls.set_min_poll_interval(interval);
+		}
 		EthFilterClient {
 			client: client,
 			miner: miner,
-			polls: Mutex::new(PollManager::new()),
+			polls: Arc::new(Mutex::new(polls)),
+			logs_chunk_size: options.logs_chunk_size,
+			max_block_range: options.max_block_range,
+			max_future_blocks: options.max_future_blocks,
+			max_result_size: options.max_result_size,
+			pending_logs_depth: options.pending_logs_depth,
+			pending_transactions_history: options.pending_transactions_history,
+			logs_timeout: options.logs_timeout.map(LogsTimeout::new),
 		}
 	}
+
+	/// Returns filters installed through this client, e.g. to uninstall them with `parity_uninstallAllFilters`.
+	pub fn filters(&self) -> SharedFilters {
+		self.polls.clone()
+	}
 }
 
 impl<C, M> Filterable for EthFilterClient<C, M> where C: BlockChainClient, M: MinerService {
@@ -80,169 +303,2291 @@ impl<C, M> Filterable for EthFilterClient<C, M> where C: BlockChainClient, M: Mi
 		self.client.chain_info().best_block_number
 	}
 
+	fn best_block_hash(&self) -> H256 {
+		self.client.chain_info().best_block_hash
+	}
+
 	fn block_hash(&self, id: BlockId) -> Option<RpcH256> {
 		self.client.block_hash(id).map(Into::into)
 	}
 
+	fn is_canonical(&self, block_hash: RpcH256) -> bool {
+		let hash: H256 = block_hash.into();
+		self.client.block_header(BlockId::Hash(hash))
+			.and_then(|header| self.client.block_hash(BlockId::Number(header.number())))
+			.map_or(false, |canonical| canonical == hash)
+	}
+
+	fn block_header(&self, id: BlockId) -> Option<RichHeader> {
+		self.client.block_header(id).map(|header| RichHeader {
+			extra_info: self.client.block_extra_info(BlockId::Hash(header.hash())).unwrap_or_default(),
+			inner: header.into(),
+		})
+	}
+
+	fn first_block_since(&self, timestamp: u64) -> Option<u64> {
+		first_block_with_timestamp(self.best_block_number(), timestamp, |number| {
+			self.client.block_header(BlockId::Number(number)).map(|header| header.timestamp())
+		})
+	}
+
 	fn pending_transactions_hashes(&self, best: u64) -> Vec<H256> {
 		self.miner.pending_transactions_hashes(best)
 	}
 
+	fn recent_pending_transactions_hashes(&self, best: u64) -> Vec<H256> {
+		self.miner.recent_pending_transactions_hashes(best)
+	}
+
+	fn is_mined_transaction(&self, hash: H256) -> bool {
+		self.client.transaction_block(TransactionId::Hash(hash)).is_some()
+	}
+
+	fn pending_transaction(&self, hash: H256) -> Option<Transaction> {
+		let best_block = self.best_block_number();
+		self.miner.transaction(best_block, &hash)
+			.map(|t| Transaction::from_pending(t, best_block, self.client.eip86_transition()))
+	}
+
 	fn logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>> {
 		Box::new(future::ok(self.client.logs(filter).into_iter().map(Into::into).collect()))
 	}
 
-	fn pending_logs(&self, block_number: u64, filter: &EthcoreFilter) -> Vec<Log> {
+	fn pending_logs(&self, block_number: u64, _depth: usize, filter: &EthcoreFilter) -> Vec<Log> {
+		// the miner only works on a single pending block
 		pending_logs(&*self.miner, block_number, filter)
 	}
 
-	fn polls(&self) -> &Mutex<PollManager<PollFilter>> { &self.polls }
+	fn has_pending_source(&self) -> bool {
+		true
+	}
+
+	fn removed_logs(&self, block_hash: H256, filter: &EthcoreFilter) -> (Vec<Log>, u64) {
+		let inner = || -> Option<Vec<H256>> {
+			let mut route = Vec::new();
+
+			let mut current_block_hash = block_hash;
+			let mut current_block_header = self.client.block_header(BlockId::Hash(current_block_hash))?;
+
+			while !self.is_canonical(current_block_hash.into()) {
+				route.push(current_block_hash);
+
+				current_block_hash = current_block_header.parent_hash();
+				current_block_header = self.client.block_header(BlockId::Hash(current_block_hash))?;
+			}
+
+			Some(route)
+		};
+
+		let route = inner().unwrap_or_default();
+		let route_len = route.len() as u64;
+		let logs = route.into_iter().flat_map(|block_hash| {
+			let mut filter = filter.clone();
+			filter.from_block = BlockId::Hash(block_hash);
+			filter.to_block = filter.from_block.clone();
+
+			self.client.logs(filter).into_iter().map(|log| {
+				let mut log: Log = log.into();
+				log.log_type = "removed".into();
+				log.removed = true;
+				log
+			})
+		}).collect();
+
+		(logs, route_len)
+	}
+
+	fn polls(&self) -> &SharedFilters { &self.polls }
+
+	fn logs_chunk_size(&self) -> Option<u64> { self.logs_chunk_size }
+
+	fn max_block_range(&self) -> Option<u64> { self.max_block_range }
+
+	fn max_result_size(&self) -> Option<usize> { self.max_result_size }
+
+	fn max_future_blocks(&self) -> Option<u64> { self.max_future_blocks }
+
+	fn pending_logs_depth(&self) -> usize { self.pending_logs_depth }
+
+	fn pending_transactions_history(&self) -> Option<usize> { self.pending_transactions_history }
+
+	fn logs_timeout(&self) -> Option<&LogsTimeout> { self.logs_timeout.as_ref() }
+}
+
+/// Returns the number of the first block up to `best_block` with a timestamp not lower
+/// than the given one, looking up timestamps of blocks by number with `block_timestamp`.
+/// Blocks whose header is not available are assumed to be older.
+pub fn first_block_with_timestamp<F>(best_block: u64, timestamp: u64, block_timestamp: F) -> Option<u64> where
+	F: Fn(u64) -> Option<u64>,
+{
+	// blocks below `low` are older, blocks from `high` on are not
+	let (mut low, mut high) = (0, best_block.saturating_add(1));
+	while low < high {
+		let middle = low + (high - low) / 2;
+		match block_timestamp(middle) {
+			Some(block_timestamp) if block_timestamp >= timestamp => high = middle,
+			_ => low = middle + 1,
+		}
+	}
+
+	match low > best_block {
+		true => None,
+		false => Some(low),
+	}
+}
+
+/// Fetches logs matching the filter, taking the topic lookup path if the filter matches any address.
+fn fetch_logs<T: Filterable>(filterable: &T, filter: EthcoreFilter) -> BoxFuture<Vec<Log>> {
+	match filter.address {
+		None => filterable.topic_logs(filter),
+		Some(_) => filterable.logs(filter),
+	}
+}
+
+/// Stream of logs, see `Filterable::logs_stream`.
+pub type BoxStream<T> = Box<Stream<Item = T, Error = Error> + Send>;
+
+/// Splits the block range of the filter into chunks of at most `chunk_size` blocks.
+/// Returns `None` if the range can't be split, e.g. because it's given by a block hash.
+fn chunk_filters<T: Filterable>(filterable: &T, filter: &EthcoreFilter, chunk_size: u64) -> Option<Vec<EthcoreFilter>> {
+	let best_block = filterable.best_block_number();
+	let block_number = |id: &BlockId| match *id {
+		BlockId::Number(n) => Some(cmp::min(n, best_block)),
+		BlockId::Earliest => Some(0),
+		BlockId::Latest => Some(best_block),
+		BlockId::Hash(_) => None,
+	};
+
+	let (from, to) = match (block_number(&filter.from_block), block_number(&filter.to_block)) {
+		(Some(from), Some(to)) if chunk_size > 0 && from <= to => (from, to),
+		_ => return None,
+	};
+
+	let mut chunks = Vec::new();
+	let mut start = from;
+	loop {
+		let end = cmp::min(start.saturating_add(chunk_size - 1), to);
+		let mut chunk = filter.clone();
+		chunk.from_block = BlockId::Number(start);
+		chunk.to_block = BlockId::Number(end);
+		chunks.push(chunk);
+
+		if end == to {
+			break;
+		}
+		start = end + 1;
+	}
+
+	Some(chunks)
+}
+
+/// Splits the block range of the filter into chunks of at most `chunk_size` blocks
+/// and fetches their logs concurrently. Logs are returned in the order of blocks,
+/// limited to `limit` logs from the end given by `direction`, together with the number
+/// of all matching logs.
+fn chunked_logs<T: Filterable>(
+	filterable: &T,
+	filter: EthcoreFilter,
+	chunk_size: u64,
+	limit: Option<usize>,
+	direction: LimitDirection,
+) -> BoxFuture<(Vec<Log>, usize)> {
+	let chunks = match chunk_filters(filterable, &filter, chunk_size) {
+		Some(chunks) => chunks.into_iter()
+			.map(|chunk| filterable.logs_with_count(chunk, limit, direction))
+			.collect::<Vec<_>>(),
+		None => return filterable.logs_with_count(filter, limit, direction),
+	};
+
+	Box::new(future::join_all(chunks)
+		.map(move |chunks| chunks.into_iter().fold((Vec::new(), 0), |(logs, count), (chunk, chunk_count)| {
+			(append_limited_logs(logs, chunk, limit, direction), count + chunk_count)
+		})))
+}
+
+/// Streams logs matching the filter in the order of blocks, see `Filterable::logs_stream`.
+fn stream_logs<T: Filterable>(filterable: &T, mut filter: EthcoreFilter) -> BoxStream<Log> {
+	// the limit is applied to the whole stream rather than to each chunk
+	let limit = filter.limit.take();
+	let chunks = match filterable.logs_chunk_size() {
+		Some(chunk_size) => chunk_filters(filterable, &filter, chunk_size).unwrap_or_else(|| vec![filter]),
+		None => vec![filter],
+	};
+
+	let logs = stream::futures_ordered(chunks.into_iter().map(|chunk| fetch_logs(filterable, chunk)))
+		.map(|logs| stream::iter_ok::<_, Error>(limit_logs(logs, None, LimitDirection::Last)))
+		.flatten();
+
+	match limit {
+		// the client keeps the last logs, so only they are buffered
+		Some(limit) => Box::new(logs
+			.fold(VecDeque::with_capacity(limit), move |mut last, log| {
+				if last.len() == limit {
+					last.pop_front();
+				}
+				if limit > 0 {
+					last.push_back(log);
+				}
+				Ok::<_, Error>(last)
+			})
+			.map(|last| stream::iter_ok::<_, Error>(last))
+			.flatten_stream()),
+		None => Box::new(logs),
+	}
+}
+
+/// Fails with `request_timeout` if the logs are not fetched within the filterable's timeout.
+fn with_timeout<T: Filterable, L: Send + 'static>(filterable: &T, logs: BoxFuture<L>) -> BoxFuture<L> {
+	let timeout = match filterable.logs_timeout() {
+		Some(timeout) => timeout.timer.sleep(timeout.duration)
+			.then(|_| -> Result<L> { Err(errors::request_timeout()) }),
+		None => return logs,
+	};
+
+	Box::new(logs.select(timeout)
+		.map(|(logs, _)| logs)
+		.map_err(|(err, _)| err))
+}
+
+/// Replaces a `safe` or `finalized` block with its number.
+fn resolve_block_tag<T: Filterable>(filterable: &T, number: BlockNumber) -> Result<BlockNumber> {
+	let resolved = match number {
+		BlockNumber::Safe => filterable.safe_block_number(),
+		BlockNumber::Finalized => filterable.finalized_block_number(),
+		number => return Ok(number),
+	};
+
+	resolved.map(BlockNumber::Num).ok_or_else(errors::unsupported_block_tag)
+}
+
+/// Replaces `safe` and `finalized` bounds of the filter with the numbers of these blocks.
+fn resolve_block_tags<T: Filterable>(filterable: &T, mut filter: Filter) -> Result<Filter> {
+	filter.from_block = match filter.from_block {
+		Some(number) => Some(resolve_block_tag(filterable, number)?),
+		None => None,
+	};
+	filter.to_block = match filter.to_block {
+		Some(number) => Some(resolve_block_tag(filterable, number)?),
+		None => None,
+	};
+	Ok(filter)
+}
+
+/// Resolves the bounds of the filter's block range to block numbers.
+/// `latest` and `pending` bounds are resolved against the given best block,
+/// `safe` and `finalized` ones have to be resolved beforehand by `resolve_block_tags`.
+fn block_range(best_block: u64, filter: &Filter) -> (u64, u64) {
+	let block_number = |number: &Option<BlockNumber>| match *number {
+		Some(BlockNumber::Num(n)) => n,
+		Some(BlockNumber::Earliest) => 0,
+		Some(BlockNumber::Latest) | Some(BlockNumber::Pending) | None => best_block,
+		Some(BlockNumber::Safe) | Some(BlockNumber::Finalized) => best_block,
+	};
+
+	(block_number(&filter.from_block), block_number(&filter.to_block))
+}
+
+/// Makes sure that the filter doesn't start too far beyond the current best block.
+fn check_from_block<T: Filterable>(filterable: &T, filter: &Filter) -> Result<()> {
+	let max_future_blocks = match filterable.max_future_blocks() {
+		Some(max_future_blocks) if filter.block_hash.is_none() => max_future_blocks,
+		_ => return Ok(()),
+	};
+
+	let best_block = filterable.best_block_number();
+	let (from, _) = block_range(best_block, filter);
+	if from > best_block.saturating_add(max_future_blocks) {
+		return Err(errors::filter_from_future_block(best_block, max_future_blocks));
+	}
+
+	Ok(())
+}
+
+/// Makes sure that the block range of the filter doesn't exceed the maximal span.
+/// `latest` and `pending` bounds are resolved against the current best block.
+fn check_block_range<T: Filterable>(filterable: &T, filter: &Filter) -> Result<()> {
+	let max_block_range = match filterable.max_block_range() {
+		Some(max_block_range) if filter.block_hash.is_none() => max_block_range,
+		_ => return Ok(()),
+	};
+
+	let (from, to) = block_range(filterable.best_block_number(), filter);
+	let span = to.saturating_sub(from);
+	if span > max_block_range {
+		return Err(errors::filter_range_too_large(max_block_range));
+	}
+
+	Ok(())
+}
+
+/// Retrieves all logs matching the given log filter, together with the range of blocks scanned.
+/// `latest` and `pending` bounds are pinned to the best block at the time of the call.
+/// If `since` is given, blocks before it are not scanned.
+fn scanned_filter_logs<T: Filterable>(filterable: &T, index: Index, since: Option<BlockNumber>) -> BoxFuture<FilterLogs> {
+	let filter = filterable.polls().lock().poll(&index.value()).cloned();
+	let filter = match filter {
+		Some(filter) => filter.modify(|filter| filter.log_filter().cloned()),
+		None => return Box::new(future::err(errors::filter_not_found())),
+	};
+	let filter = match filter {
+		Some(filter) => filter,
+		None => return Box::new(future::err(errors::filter_wrong_type("log"))),
+	};
+	let mut filter = try_bf!(resolve_block_tags(filterable, filter));
+	let since = match since {
+		Some(since) => Some(try_bf!(resolve_block_tag(filterable, since))),
+		None => None,
+	};
+
+	if let Err(err) = check_block_range(filterable, &filter) {
+		return Box::new(future::err(err));
+	}
+
+	let include_pending = filter.includes_pending() && filterable.has_pending_source();
+	let (limit, direction) = (filter.limit, filter.limit_direction());
+	let best_block = filterable.best_block_number();
+	let (from_block, to_block) = match filter.block_hash {
+		Some(ref hash) => {
+			let number = filterable.block_header(BlockId::Hash(hash.clone().into())).and_then(|header| header.inner.number);
+			(number, number)
+		},
+		None => {
+			let (from, to) = block_range(best_block, &filter);
+			let since = match since {
+				Some(BlockNumber::Num(n)) => n,
+				Some(BlockNumber::Earliest) | None => 0,
+				Some(BlockNumber::Latest) | Some(BlockNumber::Pending) => best_block,
+				Some(BlockNumber::Safe) | Some(BlockNumber::Finalized) => best_block,
+			};
+			let from = cmp::max(from, since);
+			// blocks above the best one are not scanned
+			let to = cmp::min(to, best_block);
+			filter.from_block = Some(BlockNumber::Num(from));
+			filter.to_block = Some(BlockNumber::Num(to));
+			(Some(from.into()), Some(to.into()))
+		},
+	};
+	if filter.matches_nothing() {
+		return Box::new(future::ok(FilterLogs {
+			from_block: from_block,
+			to_block: to_block,
+			logs: Vec::new(),
+			total_count: 0.into(),
+			truncated: false,
+		}));
+	}
+	let filter = try_bf!(filter.try_into_eth_filter());
+
+	// fetch pending logs.
+	let pending = if include_pending {
+		filterable.pending_logs(best_block, filterable.pending_logs_depth(), &filter)
+	} else {
+		Vec::new()
+	};
+
+	// retrieve logs asynchronously, appending pending logs.
+	let logs = match filterable.logs_chunk_size() {
+		Some(chunk_size) => chunked_logs(filterable, filter, chunk_size, limit, direction),
+		None => filterable.logs_with_count(filter, limit, direction),
+	};
+	let max_result_size = filterable.max_result_size();
+	Box::new(with_timeout(filterable, logs)
+		.map(move |(logs, count)| {
+			let total_count = (count + pending.len()).into();
+			let logs = append_limited_logs(logs, pending, limit, direction);
+			let (logs, truncated) = limit_size(logs, max_result_size, direction);
+			FilterLogs {
+				from_block: from_block,
+				to_block: to_block,
+				logs: logs,
+				total_count: total_count,
+				truncated: truncated,
+			}
+		})
+	)
+}
+
+/// Validates the log filter and installs it, retrieving logs from the given block on.
+fn create_log_poll<T: Filterable>(filterable: &T, filter: Filter, block_number: u64) -> Result<RpcU256> {
+	if filter.transaction_hash.is_some() {
+		return Err(errors::invalid_params("transactionHash", "only supported by eth_getLogs"));
+	}
+	if filter.block_hashes.is_some() {
+		return Err(errors::invalid_params("blockHashes", "only supported by eth_getLogs"));
+	}
+	// `safe` and `finalized` bounds are kept, they are resolved again whenever logs are retrieved
+	let resolved = resolve_block_tags(filterable, filter.clone())?;
+	resolved.validate()?;
+	resolved.clone().try_into_eth_filter()?;
+	check_from_block(filterable, &resolved)?;
+	check_block_range(filterable, &resolved)?;
+	let mut polls = filterable.polls().lock();
+	// share criteria with an identical filter, each filter still keeps its own cursor
+	let filter = polls.shared_filter(filter);
+	let id = polls.create_poll(SyncPollFilter::new(PollFilter::Logs {
+		block_number: block_number,
+		last_block: Default::default(),
+		previous_logs: Default::default(),
+		filter: filter,
+		created_at: filterable.best_block_number(),
+		polling: false,
+	})).map_err(poll_error)?;
+	Ok(id.into())
+}
+
+/// Returns the cursor of an imported filter, which can't be ahead of the next block of this node.
+fn imported_next_block<T: Filterable>(filterable: &T, next_block: RpcU256) -> Result<u64> {
+	let next_block: EthU256 = next_block.into();
+	let limit = filterable.best_block_number().saturating_add(1);
+	if next_block > EthU256::from(limit) {
+		return Err(errors::invalid_params("nextBlock", format!("ahead of the next block of this node, {}", limit)));
+	}
+	Ok(next_block.low_u64())
+}
+
+/// Returns the limit of a block filter, which has to let the filter advance.
+fn block_filter_limit(limit: Option<usize>) -> Result<Option<usize>> {
+	match limit {
+		Some(0) => Err(errors::invalid_params("limit", "must be greater than zero")),
+		limit => Ok(limit),
+	}
+}
+
+/// Returns all live filters with their ids and idle seconds.
+/// The polls lock is released before the filters are inspected, filters are then locked one by one.
+fn live_filters(polls: &Mutex<PollManager<SyncPollFilter>>) -> Vec<(usize, SyncPollFilter, u64)> {
+	polls.lock().iter()
+		.map(|(id, filter, idle_seconds)| (id, filter.clone(), idle_seconds))
+		.collect()
+}
+
+/// Returns the live filter with given id and its idle seconds, without counting as polling it.
+fn live_filter(polls: &Mutex<PollManager<SyncPollFilter>>, id: usize) -> Result<(SyncPollFilter, u64)> {
+	polls.lock().iter()
+		.find(|&(poll_id, _, _)| poll_id == id)
+		.map(|(_, filter, idle_seconds)| (filter.clone(), idle_seconds))
+		.ok_or_else(errors::filter_not_found)
+}
+
+fn describe_filter(id: usize, filter: &PollFilter, idle_seconds: u64) -> FilterInfo {
+	FilterInfo {
+		id: id.into(),
+		filter_type: filter.kind().into(),
+		idle_seconds: idle_seconds,
+		created_at: filter.created_at().into(),
+		filter: filter.log_filter().cloned(),
+	}
+}
+
+/// Returns ids, types and idle times of all given filters, for `parity_listFilters`.
+pub fn list_filters(polls: &Mutex<PollManager<SyncPollFilter>>) -> Vec<FilterInfo> {
+	let mut filters = live_filters(polls);
+	filters.sort_by_key(|&(id, _, _)| id);
+
+	filters.into_iter()
+		.map(|(id, filter, idle_seconds)| filter.modify(|filter| describe_filter(id, filter, idle_seconds)))
+		.collect()
+}
+
+/// Returns details of the filter with given id, for `parity_getFilterInfo`.
+pub fn filter_info(polls: &Mutex<PollManager<SyncPollFilter>>, index: Index) -> Result<FilterInfo> {
+	let id = index.value();
+	let (filter, idle_seconds) = live_filter(polls, id)?;
+	Ok(filter.modify(|filter| describe_filter(id, filter, idle_seconds)))
+}
+
+/// Returns the number of given filters, in total and by filter type, for `parity_filterStats`.
+pub fn filter_stats(polls: &Mutex<PollManager<SyncPollFilter>>) -> FilterStats {
+	let mut counts = BTreeMap::new();
+	for (_, filter, _) in live_filters(polls) {
+		*counts.entry(filter.modify(|filter| filter.kind())).or_insert(0) += 1;
+	}
+	FilterStats {
+		total: counts.values().sum::<usize>().into(),
+		by_type: counts.into_iter().map(|(kind, count)| (kind.into(), count.into())).collect(),
+	}
+}
+
+/// Returns the state of the filter with given id, for `parity_exportFilter`.
+pub fn export_filter(polls: &Mutex<PollManager<SyncPollFilter>>, index: Index) -> Result<FilterState> {
+	let (filter, _) = live_filter(polls, index.value())?;
+	Ok(filter.modify(|filter| filter.state()))
+}
+
+fn poll_error(error: PollError) -> ::jsonrpc_core::Error {
+	match error {
+		PollError::LimitReached => errors::filter_limit_reached(),
+		PollError::IdInUse(id) => errors::internal("Filter id is already in use", id),
+		PollError::Throttled => errors::filter_polled_too_often(),
+	}
+}
+
+/// Looks up the filter with given id in order to return its changes.
+fn changes_filter(polls: &mut PollManager<SyncPollFilter>, id: usize) -> Result<SyncPollFilter> {
+	match polls.poll_changes(&id) {
+		Err(err) => Err(poll_error(err)),
+		Ok(None) => Err(errors::filter_not_found()),
+		Ok(Some(filter)) => Ok(filter.clone()),
+	}
+}
+
+/// Marks a log filter as being polled until the poll completes or is dropped.
+struct InFlightPoll(SyncPollFilter);
+
+impl Drop for InFlightPoll {
+	fn drop(&mut self) {
+		self.0.modify(|filter| if let PollFilter::Logs { ref mut polling, .. } = *filter {
+			*polling = false;
+		});
+	}
+}
+
+/// Returns changes of the given filter since its last poll.
+/// Only the filter itself is locked, so that distinct filters can be polled concurrently.
+fn poll_filter_changes<T: Filterable>(filterable: &T, filter: SyncPollFilter) -> BoxFuture<FilterChanges> {
+	let state = filter.clone();
+	filter.modify(|filter| -> BoxFuture<FilterChanges> {
+		Box::new(match *filter {
+			PollFilter::Block { ref mut next_block, ref mut recent_hashes, limit, .. } => {
+				let current_number = filterable.best_block_number();
+				let current_hash = filterable.best_block_hash();
+
+				// nothing has changed since the last poll
+				let hashes = if recent_hashes.back() == Some(&(current_number, current_hash)) {
+					Vec::new()
+				} else {
+					// drop reported blocks which are no longer canonical,
+					// their replacements have to be reported again
+					let mut first_number = *next_block;
+					while let Some(&(number, hash)) = recent_hashes.back() {
+						if number <= current_number && filterable.is_canonical(hash.into()) {
+							break;
+						}
+						recent_hashes.pop_back();
+						first_number = cmp::min(first_number, number);
+					}
+
+					// +1, cause we want to return hashes including current block hash.
+					let mut numbers = first_number..current_number.saturating_add(1);
+					if let Some(limit) = limit {
+						// the remaining blocks are reported by the next polls
+						numbers.end = cmp::min(numbers.end, first_number.saturating_add(limit as u64));
+					}
+
+					// stop at the first block whose hash can't be resolved,
+					// so that it's reported by the next polls instead of being skipped
+					let hashes = numbers
+						.map(|number| filterable.block_hash(BlockId::Number(number)).map(|hash| (number, hash)))
+						.take_while(Option::is_some)
+						.filter_map(|hash| hash)
+						.collect::<Vec<(u64, RpcH256)>>();
+					let next_number = hashes.last().map_or(first_number, |&(number, _)| number + 1);
+
+					for &(number, ref hash) in &hashes {
+						if recent_hashes.len() == BLOCK_FILTER_HISTORY {
+							recent_hashes.pop_front();
+						}
+						recent_hashes.push_back((number, hash.clone().into()));
+					}
+					*next_block = next_number;
+
+					hashes.into_iter().map(|(_, hash)| hash).collect()
+				};
+
+				Either::A(future::ok(FilterChanges::Hashes(hashes)))
+			},
+			PollFilter::BlockHeaders { ref mut next_block, .. } => {
+				let current_number = filterable.best_block_number();
+				// +1, cause we want to return the current block header too.
+				// stop at the first header which can't be resolved,
+				// so that it's reported by the next polls instead of being skipped
+				let headers = (*next_block..current_number.saturating_add(1)).into_iter()
+					.map(|number| filterable.block_header(BlockId::Number(number)))
+					.take_while(Option::is_some)
+					.filter_map(|header| header)
+					.collect::<Vec<_>>();
+
+				*next_block += headers.len() as u64;
+
+				Either::A(future::ok(FilterChanges::Headers(headers)))
+			},
+			PollFilter::PendingTransaction { ref mut previous_hashes, ref mut evicted_hashes, ref mut mined_hashes, full, limit, newest_first, mark_returned, .. } => {
+				// get hashes of pending transactions
+				let best_block = filterable.best_block_number();
+				let current_hashes = match newest_first {
+					true => filterable.recent_pending_transactions_hashes(best_block),
+					false => filterable.pending_transactions_hashes(best_block),
+				};
+
+				// find all new hashes, skipping recently evicted ones which came back,
+				// unless they were mined and a reorganisation returned them to the pool
+				let (mut new_hashes, returned_hashes): (Vec<H256>, Vec<H256>) = current_hashes
+					.iter()
+					.filter(|hash| !previous_hashes.contains(hash))
+					.cloned()
+					.partition(|hash| !evicted_hashes.contains(hash) || mined_hashes.contains(hash));
+
+				// the remaining transactions are reported by the next polls
+				let deferred_hashes: HashSet<H256> = match limit {
+					Some(limit) if new_hashes.len() > limit => new_hashes.split_off(limit).into_iter().collect(),
+					_ => HashSet::new(),
+				};
+
+				// flag reported transactions which were mined before, and remember
+				// reported transactions which left the pool because they were mined
+				let returned: Vec<bool> = new_hashes.iter().map(|hash| mined_hashes.contains(hash)).collect();
+				if mark_returned {
+					mined_hashes.remove_all(&new_hashes);
+					let current: HashSet<&H256> = current_hashes.iter().collect();
+					mined_hashes.extend(previous_hashes.iter()
+						.filter(|hash| !current.contains(hash) && filterable.is_mined_transaction(**hash))
+						.cloned());
+					mined_hashes.truncate_oldest(MINED_TRANSACTIONS_HISTORY);
+				}
+
+				// remember reported transactions which left the pool
+				if let Some(history) = filterable.pending_transactions_history() {
+					evicted_hashes.remove_all(&returned_hashes);
+					let current: HashSet<&H256> = current_hashes.iter().collect();
+					evicted_hashes.extend(previous_hashes.iter().filter(|hash| !current.contains(hash)).cloned());
+					evicted_hashes.truncate_oldest(history);
+				}
+
+				// save hashes of reported pending transactions, reusing the set's allocation
+				previous_hashes.clear();
+				previous_hashes.extend(current_hashes.into_iter().filter(|hash| !deferred_hashes.contains(hash)));
+
+				// return new transactions or their hashes
+				let changes = if full {
+					FilterChanges::Transactions(new_hashes.into_iter()
+						.filter_map(|hash| filterable.pending_transaction(hash))
+						.collect())
+				} else if mark_returned {
+					FilterChanges::PendingHashes(new_hashes.into_iter()
+						.zip(returned)
+						.map(|(hash, returned)| PendingTransactionHash { hash: hash.into(), returned: returned })
+						.collect())
+				} else {
+					FilterChanges::Hashes(new_hashes.into_iter().map(Into::into).collect())
+				};
+
+				Either::A(future::ok(changes))
+			},
+			PollFilter::Logs { block_number, ref last_block, ref previous_logs, ref filter, ref mut polling, .. } => {
+				// don't hit the backend for logs which can't exist
+				if filter.matches_nothing() {
+					return Box::new(future::ok(FilterChanges::Logs(Vec::new())));
+				}
+
+				// a poll started from the same cursor would report the same logs again,
+				// and committing it after this one would move the filter back
+				if *polling {
+					return Box::new(future::err(errors::filter_polled_too_often()));
+				}
+
+				// retrive the current block number
+				let current_number = filterable.best_block_number();
+
+				// check if we need to check pending hashes
+				let include_pending = filter.includes_pending() && filterable.has_pending_source();
+
+				// single block filters report logs of their block on the first poll only
+				let single_block = filter.block_hash.is_some();
+				let already_polled = last_block.last_seen().is_some();
+
+				// build appropriate filter
+				let (limit, direction) = (filter.limit, filter.limit_direction());
+				let mut filter = match resolve_block_tags(filterable, (**filter).clone()).and_then(Filter::try_into_eth_filter) {
+					Ok(filter) => filter,
+					Err(err) => return Box::new(future::err(err)),
+				};
+
+				// retrieve logs from blocks which are no longer canonical
+				// and rewind to the first block replaced by the reorg
+				let reorged = match single_block {
+					true => None,
+					false => last_block.reorged(|hash| filterable.is_canonical(hash.into())),
+				};
+				let (reorg, reorg_len) = match reorged {
+					Some(hash) => filterable.removed_logs(hash, &filter),
+					None => (Vec::new(), 0),
+				};
+
+				if !single_block {
+					filter.from_block = BlockId::Number(block_number.saturating_sub(reorg_len));
+					filter.to_block = BlockId::Latest;
+				}
+
+				// retrieve pending logs
+				let (pending, seen_logs) = if include_pending {
+					let pending_logs = filterable.pending_logs(current_number, filterable.pending_logs_depth(), &filter);
+
+					// remove logs about which client was already notified about
+					let keys = log_keys(&pending_logs);
+					let new_pending_logs: Vec<_> = pending_logs.into_iter()
+						.zip(keys.iter())
+						.filter(|&(_, key)| !previous_logs.contains(key))
+						.map(|(log, _)| log)
+						.collect();
+
+					(new_pending_logs, keys.into_iter().collect())
+				} else {
+					// don't hold on to logs reported while pending logs were included
+					(Vec::new(), HashSet::new())
+				};
+
+				// the current block, used to detect a reorg on the next poll
+				let current_hash = filterable.block_hash(BlockId::Number(current_number));
+
+				// the filter only moves on once the changes are fetched,
+				// so that a failed poll reports the same changes again
+				*polling = true;
+				let in_flight = InFlightPoll(state);
+				let commit = move || in_flight.0.modify(|filter| {
+					if let PollFilter::Logs { ref mut block_number, ref mut last_block, ref mut previous_logs, .. } = *filter {
+						// save the number of the next block as a first block from which
+						// we want to get logs
+						*block_number = current_number.saturating_add(1);
+						if let Some(hash) = current_hash {
+							last_block.update(current_number, hash.into());
+						}
+						// save all pending logs retrieved by client
+						*previous_logs = seen_logs;
+					}
+				});
+
+				// retrieve logs in range from_block..min(BlockId::Latest..to_block)
+				let logs: BoxFuture<Vec<Log>> = if single_block && already_polled {
+					Box::new(future::ok(Vec::new()))
+				} else {
+					fetch_logs(filterable, filter)
+				};
+				Either::B(with_timeout(filterable, logs)
+					.map(move |logs| {
+						commit();
+						logs
+					})
+					.map(move |logs| append_limited_logs(reorg, logs, limit, direction)) // prepend removed logs
+					.map(move |logs| append_limited_logs(logs, pending, limit, direction)) // append fetched pending logs
+					.map(FilterChanges::Logs))
+			}
+		})
+	})
 }
 
+/// Returns changes of all given filters since their last poll, keyed by filter id.
+/// Failures, e.g. of filters which couldn't be looked up, are reported per filter.
+fn poll_filters_changes<T: Filterable>(filterable: &T, filters: Vec<(usize, Result<SyncPollFilter>)>) -> BoxFuture<BTreeMap<String, BatchFilterChanges>> {
+	let changes = filters.into_iter()
+		.map(|(id, filter)| {
+			let changes: BoxFuture<FilterChanges> = match filter {
+				Ok(filter) => poll_filter_changes(filterable, filter),
+				Err(err) => Box::new(future::err(err)),
+			};
+			changes.then(move |changes| Ok::<_, Error>((
+				format!("0x{:x}", id),
+				match changes {
+					Ok(changes) => BatchFilterChanges::Changes(changes),
+					Err(error) => BatchFilterChanges::Error(error),
+				},
+			)))
+		})
+		.collect::<Vec<_>>();
 
+	Box::new(future::join_all(changes).map(|changes| changes.into_iter().collect()))
+}
 
 impl<T: Filterable + Send + Sync + 'static> EthFilter for T {
 	fn new_filter(&self, filter: Filter) -> Result<RpcU256> {
-		let mut polls = self.polls().lock();
 		let block_number = self.best_block_number();
-		let id = polls.create_poll(PollFilter::Logs(block_number, Default::default(), filter));
+		create_log_poll(self, filter, block_number)
+	}
+
+	fn new_block_filter(&self, limit: Trailing<usize>) -> Result<RpcU256> {
+		let limit = block_filter_limit(limit.into())?;
+		let mut polls = self.polls().lock();
+		let best_block = self.best_block_number();
+		let mut recent_hashes = VecDeque::with_capacity(BLOCK_FILTER_HISTORY);
+		recent_hashes.push_back((best_block, self.best_block_hash()));
+		let id = polls.create_poll(SyncPollFilter::new(PollFilter::Block {
+			// +1, since we don't want to include the current block
+			next_block: best_block.saturating_add(1),
+			recent_hashes: recent_hashes,
+			limit: limit,
+			created_at: best_block,
+		})).map_err(poll_error)?;
 		Ok(id.into())
 	}
 
-	fn new_block_filter(&self) -> Result<RpcU256> {
+	fn new_block_header_filter(&self) -> Result<RpcU256> {
 		let mut polls = self.polls().lock();
-		// +1, since we don't want to include the current block
-		let id = polls.create_poll(PollFilter::Block(self.best_block_number() + 1));
+		let best_block = self.best_block_number();
+		let id = polls.create_poll(SyncPollFilter::new(PollFilter::BlockHeaders {
+			// +1, since we don't want to include the current block
+			next_block: best_block.saturating_add(1),
+			created_at: best_block,
+		})).map_err(poll_error)?;
 		Ok(id.into())
 	}
 
-	fn new_pending_transaction_filter(&self) -> Result<RpcU256> {
+	fn new_pending_transaction_filter(&self, options: Trailing<PendingTransactionFilterOptions>) -> Result<RpcU256> {
+		let options = options.unwrap_or_default();
 		let mut polls = self.polls().lock();
 		let best_block = self.best_block_number();
 		let pending_transactions = self.pending_transactions_hashes(best_block);
-		let id = polls.create_poll(PollFilter::PendingTransaction(pending_transactions));
+		let id = polls.create_poll(SyncPollFilter::new(PollFilter::PendingTransaction {
+			previous_hashes: pending_transactions.into_iter().collect(),
+			evicted_hashes: HashHistory::default(),
+			mined_hashes: HashHistory::default(),
+			full: options.full,
+			limit: options.limit,
+			newest_first: options.newest_first,
+			mark_returned: options.mark_returned,
+			created_at: best_block,
+		})).map_err(poll_error)?;
 		Ok(id.into())
 	}
 
 	fn filter_changes(&self, index: Index) -> BoxFuture<FilterChanges> {
-		let mut polls = self.polls().lock();
-		Box::new(match polls.poll_mut(&index.value()) {
-			None => Either::A(future::ok(FilterChanges::Empty)),
-			Some(filter) => match *filter {
-				PollFilter::Block(ref mut block_number) => {
-					// +1, cause we want to return hashes including current block hash.
-					let current_number = self.best_block_number() + 1;
-					let hashes = (*block_number..current_number).into_iter()
-						.map(BlockId::Number)
-						.filter_map(|id| self.block_hash(id))
-						.collect::<Vec<RpcH256>>();
-
-					*block_number = current_number;
-
-					Either::A(future::ok(FilterChanges::Hashes(hashes)))
-				},
-				PollFilter::PendingTransaction(ref mut previous_hashes) => {
-					// get hashes of pending transactions
-					let best_block = self.best_block_number();
-					let current_hashes = self.pending_transactions_hashes(best_block);
-
-					let new_hashes =
-					{
-						let previous_hashes_set = previous_hashes.iter().collect::<HashSet<_>>();
-
-						//	find all new hashes
-						current_hashes
-							.iter()
-							.filter(|hash| !previous_hashes_set.contains(hash))
-							.cloned()
-							.map(Into::into)
-							.collect::<Vec<RpcH256>>()
-					};
-
-					// save all hashes of pending transactions
-					*previous_hashes = current_hashes;
-
-					// return new hashes
-					Either::A(future::ok(FilterChanges::Hashes(new_hashes)))
-				},
-				PollFilter::Logs(ref mut block_number, ref mut previous_logs, ref filter) => {
-					// retrive the current block number
-					let current_number = self.best_block_number();
-
-					// check if we need to check pending hashes
-					let include_pending = filter.to_block == Some(BlockNumber::Pending);
-
-					// build appropriate filter
-					let mut filter: EthcoreFilter = filter.clone().into();
-					filter.from_block = BlockId::Number(*block_number);
-					filter.to_block = BlockId::Latest;
-
-					// retrieve pending logs
-					let pending = if include_pending {
-						let pending_logs = self.pending_logs(current_number, &filter);
-
-						// remove logs about which client was already notified about
-						let new_pending_logs: Vec<_> = pending_logs.iter()
-							.filter(|p| !previous_logs.contains(p))
-							.cloned()
-							.collect();
-
-						// save all logs retrieved by client
-						*previous_logs = pending_logs.into_iter().collect();
-
-						new_pending_logs
-					} else {
-						Vec::new()
-					};
-
-					// save the number of the next block as a first block from which
-					// we want to get logs
-					*block_number = current_number + 1;
-
-					// retrieve logs in range from_block..min(BlockId::Latest..to_block)
-					let limit = filter.limit;
-					Either::B(self.logs(filter)
-						.map(move |mut logs| { logs.extend(pending); logs }) // append fetched pending logs
-						.map(move |logs| limit_logs(logs, limit)) // limit the logs
-						.map(FilterChanges::Logs))
-				}
-			}
-		})
+		let filter = try_bf!(changes_filter(&mut self.polls().lock(), index.value()));
+		poll_filter_changes(self, filter)
 	}
 
-	fn filter_logs(&self, index: Index) -> BoxFuture<Vec<Log>> {
-		let filter = {
+	fn filter_changes_batch(&self, indices: Vec<Index>) -> BoxFuture<BTreeMap<String, BatchFilterChanges>> {
+		let filters = {
 			let mut polls = self.polls().lock();
-
-			match polls.poll(&index.value()) {
-				Some(&PollFilter::Logs(ref _block_number, ref _previous_log, ref filter)) => filter.clone(),
-				// just empty array
-				_ => return Box::new(future::ok(Vec::new())),
-			}
+			indices.into_iter()
+				.map(|index| (index.value(), changes_filter(&mut polls, index.value())))
+				.collect()
 		};
+		poll_filters_changes(self, filters)
+	}
 
-		let include_pending = filter.to_block == Some(BlockNumber::Pending);
-		let filter: EthcoreFilter = filter.into();
+	fn filter_changes_by_type(&self, kind: String, indices: Vec<Index>) -> BoxFuture<BTreeMap<String, BatchFilterChanges>> {
+		if !FILTER_KINDS.contains(&kind.as_str()) {
+			return Box::new(future::err(errors::invalid_params("type", format!("expected one of {:?}", FILTER_KINDS))));
+		}
 
-		// fetch pending logs.
-		let pending = if include_pending {
-			let best_block = self.best_block_number();
-			self.pending_logs(best_block, &filter)
-		} else {
-			Vec::new()
+		// filters which don't exist are kept, so that they are reported like in a batch,
+		// filters of other types are skipped without counting as polled
+		let filters = {
+			let mut polls = self.polls().lock();
+			let ids = indices.into_iter()
+				.map(|index| index.value())
+				.filter(|id| polls.peek(id).map_or(true, |filter| filter.modify(|filter| filter.kind()) == kind))
+				.collect::<Vec<_>>();
+			ids.into_iter()
+				.map(|id| (id, changes_filter(&mut polls, id)))
+				.collect()
 		};
+		poll_filters_changes(self, filters)
+	}
 
-		// retrieve logs asynchronously, appending pending logs.
-		let limit = filter.limit;
-		let logs = self.logs(filter);
-		Box::new(logs
-			.map(move |mut logs| { logs.extend(pending); logs })
-			.map(move |logs| limit_logs(logs, limit))
-		)
+	fn filter_logs(&self, index: Index) -> BoxFuture<Vec<Log>> {
+		Box::new(scanned_filter_logs(self, index, None).map(|scanned| scanned.logs))
 	}
 
-	fn uninstall_filter(&self, index: Index) -> Result<bool> {
-		self.polls().lock().remove_poll(&index.value());
-		Ok(true)
+	fn filter_logs_with_range(&self, index: Index) -> BoxFuture<FilterLogs> {
+		scanned_filter_logs(self, index, None)
+	}
+
+	fn filter_changes_since(&self, index: Index, since: BlockNumber) -> BoxFuture<FilterLogs> {
+		scanned_filter_logs(self, index, Some(since))
+	}
+
+	fn filter_changes_since_timestamp(&self, index: Index, timestamp: u64) -> BoxFuture<FilterLogs> {
+		// only pending logs are newer than all blocks
+		let since = self.first_block_since(timestamp)
+			.unwrap_or_else(|| self.best_block_number().saturating_add(1));
+		scanned_filter_logs(self, index, Some(BlockNumber::Num(since)))
+	}
+
+	fn uninstall_filter(&self, index: Index) -> Result<bool> {
+		match self.polls().lock().remove_poll(&index.value()) {
+			true => Ok(true),
+			false => Err(errors::filter_not_found()),
+		}
+	}
+
+	fn drain_filter(&self, index: Index) -> BoxFuture<FilterChanges> {
+		let id = index.value();
+		let filter = try_bf!(changes_filter(&mut self.polls().lock(), id));
+		let drained = filter.clone();
+		let polls = self.polls().clone();
+
+		// the filter is only removed once its changes are fetched, so that a failed drain
+		// can be retried, and only if it wasn't uninstalled and replaced meanwhile
+		Box::new(poll_filter_changes(self, filter).map(move |changes| {
+			let mut polls = polls.lock();
+			if polls.peek(&id).map_or(false, |filter| filter.is_same(&drained)) {
+				polls.remove_poll(&id);
+			}
+			changes
+		}))
+	}
+
+	fn pending_transaction_count(&self) -> Result<RpcU256> {
+		let best_block = self.best_block_number();
+		Ok(self.pending_transactions_hashes(best_block).len().into())
+	}
+
+	fn safe_block(&self) -> Result<Option<RichHeader>> {
+		Ok(self.safe_block_number().and_then(|number| self.block_header(BlockId::Number(number))))
+	}
+
+	fn finalized_block(&self) -> Result<Option<RichHeader>> {
+		Ok(self.finalized_block_number().and_then(|number| self.block_header(BlockId::Number(number))))
+	}
+
+	fn test_filter(&self, filter: Filter, log: Log) -> Result<bool> {
+		let filter = resolve_block_tags(self, filter)?;
+		filter.validate()?;
+		Ok(test_filter(&filter, &log))
+	}
+
+	fn import_filter(&self, state: FilterState) -> Result<RpcU256> {
+		let best_block = self.best_block_number();
+		let filter = match state {
+			FilterState::Logs { filter, next_block } => {
+				let block_number = imported_next_block(self, next_block)?;
+				return create_log_poll(self, filter, block_number);
+			},
+			FilterState::Block { next_block, limit } => PollFilter::Block {
+				next_block: imported_next_block(self, next_block)?,
+				recent_hashes: VecDeque::with_capacity(BLOCK_FILTER_HISTORY),
+				limit: block_filter_limit(limit)?,
+				created_at: best_block,
+			},
+			FilterState::BlockHeaders { next_block } => PollFilter::BlockHeaders {
+				next_block: imported_next_block(self, next_block)?,
+				created_at: best_block,
+			},
+			FilterState::PendingTransaction { full, limit, newest_first, mark_returned } => {
+				if full && mark_returned {
+					return Err(errors::invalid_params("markReturned", "can't be combined with full"));
+				}
+				// transactions already in the pool are not reported, like for a new filter
+				PollFilter::PendingTransaction {
+					previous_hashes: self.pending_transactions_hashes(best_block).into_iter().collect(),
+					evicted_hashes: HashHistory::default(),
+					mined_hashes: HashHistory::default(),
+					full: full,
+					limit: limit,
+					newest_first: newest_first,
+					mark_returned: mark_returned,
+					created_at: best_block,
+				}
+			},
+		};
+		let id = self.polls().lock().create_poll(SyncPollFilter::new(filter)).map_err(poll_error)?;
+		Ok(id.into())
+	}
+}
+
+/// Stream of changes of an installed filter, polled with `filter_changes` on every tick.
+/// Ends once the filter is uninstalled.
+pub struct FilterChangeStream<F, T> {
+	filter: Arc<F>,
+	index: Index,
+	ticks: T,
+	pending: Option<BoxFuture<FilterChanges>>,
+	done: bool,
+}
+
+impl<F: EthFilter> FilterChangeStream<F, Interval> {
+	/// Creates new `FilterChangeStream` polling filter with given id every `poll_interval`.
+	pub fn with_interval(filter: Arc<F>, index: Index, poll_interval: Duration) -> Self {
+		FilterChangeStream::new(filter, index, Timer::default().interval(poll_interval))
+	}
+}
+
+impl<F: EthFilter, T> FilterChangeStream<F, T> {
+	/// Creates new `FilterChangeStream` polling filter with given id on every item of `ticks`.
+	pub fn new(filter: Arc<F>, index: Index, ticks: T) -> Self {
+		FilterChangeStream {
+			filter: filter,
+			index: index,
+			ticks: ticks,
+			pending: None,
+			done: false,
+		}
+	}
+}
+
+impl<F, T> Stream for FilterChangeStream<F, T> where
+	F: EthFilter,
+	T: Stream<Item = ()>,
+	T::Error: fmt::Debug,
+{
+	type Item = FilterChanges;
+	type Error = Error;
+
+	fn poll(&mut self) -> Poll<Option<FilterChanges>, Error> {
+		loop {
+			if self.done {
+				return Ok(Async::Ready(None));
+			}
+
+			if let Some(mut pending) = self.pending.take() {
+				return match pending.poll() {
+					Ok(Async::NotReady) => {
+						self.pending = Some(pending);
+						Ok(Async::NotReady)
+					},
+					Ok(Async::Ready(changes)) => Ok(Async::Ready(Some(changes))),
+					Err(ref err) if err.code == errors::filter_not_found().code => {
+						self.done = true;
+						Ok(Async::Ready(None))
+					},
+					Err(err) => {
+						self.done = true;
+						Err(err)
+					},
+				};
+			}
+
+			match self.ticks.poll() {
+				Ok(Async::Ready(Some(()))) => self.pending = Some(self.filter.filter_changes(self.index.clone())),
+				Ok(Async::Ready(None)) => self.done = true,
+				Ok(Async::NotReady) => return Ok(Async::NotReady),
+				Err(err) => {
+					self.done = true;
+					return Err(errors::internal("Filter poll timer failed", err));
+				},
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cmp;
+	use std::sync::Arc;
+	use std::thread;
+	use std::time::Duration;
+	use std::u64;
+	use ethcore::filter::Filter as EthcoreFilter;
+	use ethcore::client::{BlockId, EachBlockWith, ImportBlock, TestBlockChainClient};
+	use ethcore::encoded;
+	use ethcore::header::{Header, Seal};
+	use ethereum_types::H256;
+	use jsonrpc_core::{BoxFuture, ErrorCode, IoHandler};
+	use jsonrpc_core::futures::{future, Future, Stream};
+	use jsonrpc_core::futures::sync::{mpsc, oneshot};
+	use parking_lot::Mutex;
+	use rlp::RlpStream;
+	use serde_json;
+	use v1::tests::helpers::TestMinerService;
+	use v1::traits::EthFilter;
+	use v1::types::{FilterChanges, Index, Log, RichHeader, Transaction, H256 as RpcH256, U256 as RpcU256};
+	use v1::helpers::{errors, PollFilter, PollManager, SharedFilters};
+	use super::{export_filter, EthFilterClient, Filterable, FilterChangeStream, LogsTimeout};
+
+	struct TestChain {
+		best_block: u64,
+		// blocks starting from this number are replaced by the given fork
+		reorged_from: u64,
+		fork: u64,
+		block_hash_calls: usize,
+		requested_logs: Vec<(BlockId, BlockId)>,
+		// logs requests never complete
+		stalled: bool,
+		// logs are sent from another thread once requested, like by an on-demand backend
+		on_demand: bool,
+		// logs of address-less filters are looked up by topics
+		topic_index: bool,
+		topic_logs_calls: usize,
+		// hash and header of this block can't be resolved
+		missing_block: Option<u64>,
+		// number of pending blocks, each with a single log
+		pending_blocks: usize,
+		// hashes of pending transactions, in the order they were queued
+		pending_transactions: Vec<H256>,
+		// pending logs can't be retrieved
+		no_pending_source: bool,
+		// hashes of transactions included in canonical blocks
+		mined_transactions: Vec<H256>,
+		// maximal serialized size of filter logs
+		max_result_size: Option<usize>,
+		// number of data bytes of each log
+		log_data_size: usize,
+	}
+
+	impl Default for TestChain {
+		fn default() -> Self {
+			TestChain {
+				best_block: 100,
+				reorged_from: u64::MAX,
+				fork: 0,
+				block_hash_calls: 0,
+				requested_logs: Vec::new(),
+				stalled: false,
+				on_demand: false,
+				topic_index: false,
+				topic_logs_calls: 0,
+				missing_block: None,
+				pending_blocks: 0,
+				pending_transactions: Vec::new(),
+				no_pending_source: false,
+				mined_transactions: Vec::new(),
+				max_result_size: None,
+				log_data_size: 0,
+			}
+		}
+	}
+
+	impl TestChain {
+		fn hash(&self, number: u64) -> H256 {
+			match number >= self.reorged_from {
+				true => H256::from(self.fork * 1_000_000 + number),
+				false => H256::from(number),
+			}
+		}
+
+		// a block every 15 seconds, with a pause of 100 seconds after block 50
+		fn timestamp(&self, number: u64) -> u64 {
+			match number > 50 {
+				true => 1_000 + number * 15 + 100,
+				false => 1_000 + number * 15,
+			}
+		}
+	}
+
+	struct TestFilterable {
+		chunk_size: Option<u64>,
+		logs_timeout: Option<LogsTimeout>,
+		pending_logs_depth: usize,
+		chain: Arc<Mutex<TestChain>>,
+		polls: SharedFilters,
+	}
+
+	impl Filterable for TestFilterable {
+		fn best_block_number(&self) -> u64 {
+			self.chain.lock().best_block
+		}
+
+		fn best_block_hash(&self) -> H256 {
+			let chain = self.chain.lock();
+			chain.hash(chain.best_block)
+		}
+
+		fn block_header(&self, id: BlockId) -> Option<RichHeader> {
+			let chain = self.chain.lock();
+			match id {
+				BlockId::Number(number) if chain.missing_block == Some(number) => None,
+				BlockId::Number(number) if number <= chain.best_block => {
+					let mut header = Header::new();
+					header.set_number(number);
+					header.set_timestamp(chain.timestamp(number));
+					Some(RichHeader {
+						inner: encoded::Header::new(header.rlp(Seal::With)).into(),
+						extra_info: Default::default(),
+					})
+				},
+				_ => None,
+			}
+		}
+
+		fn first_block_since(&self, timestamp: u64) -> Option<u64> {
+			let chain = self.chain.lock();
+			super::first_block_with_timestamp(chain.best_block, timestamp, |number| Some(chain.timestamp(number)))
+		}
+
+		fn block_hash(&self, id: BlockId) -> Option<RpcH256> {
+			let mut chain = self.chain.lock();
+			chain.block_hash_calls += 1;
+			match id {
+				BlockId::Number(number) if chain.missing_block == Some(number) => None,
+				BlockId::Number(number) if number <= chain.best_block => Some(chain.hash(number).into()),
+				_ => None,
+			}
+		}
+
+		fn is_canonical(&self, block_hash: RpcH256) -> bool {
+			let mut chain = self.chain.lock();
+			chain.block_hash_calls += 1;
+			let hash: H256 = block_hash.into();
+			// hashes of forked blocks are offset by the fork
+			let number = match hash.low_u64() {
+				n if chain.fork > 0 && n >= chain.fork * 1_000_000 => n - chain.fork * 1_000_000,
+				n => n,
+			};
+			chain.missing_block != Some(number) && number <= chain.best_block && chain.hash(number) == hash
+		}
+
+		fn pending_transactions_hashes(&self, _block_number: u64) -> Vec<H256> {
+			self.chain.lock().pending_transactions.clone()
+		}
+
+		fn recent_pending_transactions_hashes(&self, _block_number: u64) -> Vec<H256> {
+			self.chain.lock().pending_transactions.iter().rev().cloned().collect()
+		}
+
+		fn is_mined_transaction(&self, hash: H256) -> bool {
+			self.chain.lock().mined_transactions.contains(&hash)
+		}
+
+		fn pending_transaction(&self, _hash: H256) -> Option<Transaction> {
+			None
+		}
+
+		fn logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>> {
+			let mut chain = self.chain.lock();
+			chain.requested_logs.push((filter.from_block.clone(), filter.to_block.clone()));
+			if chain.stalled {
+				return Box::new(future::empty());
+			}
+
+			// one log per block
+			let data = vec![0xff; chain.log_data_size];
+			let logs = match (filter.from_block, filter.to_block) {
+				(BlockId::Number(from), BlockId::Number(to)) => (from..to + 1).map(log).map(|mut log| {
+					log.data = data.clone().into();
+					log
+				}).collect(),
+				_ => Vec::new(),
+			};
+			if chain.on_demand {
+				let (sender, receiver) = oneshot::channel();
+				thread::spawn(move || sender.send(logs));
+				return Box::new(receiver.map_err(|_| errors::internal("Logs request was canceled", "")));
+			}
+			Box::new(future::ok(logs))
+		}
+
+		fn topic_logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>> {
+			{
+				let mut chain = self.chain.lock();
+				if chain.topic_index {
+					chain.topic_logs_calls += 1;
+				}
+			}
+			// the topic index finds the same logs as the general path
+			self.logs(filter)
+		}
+
+		fn safe_block_number(&self) -> Option<u64> {
+			Some(self.chain.lock().best_block - 5)
+		}
+
+		fn finalized_block_number(&self) -> Option<u64> {
+			Some(self.chain.lock().best_block - 10)
+		}
+
+		fn pending_logs(&self, block_number: u64, depth: usize, _filter: &EthcoreFilter) -> Vec<Log> {
+			assert!(!self.chain.lock().no_pending_source, "pending logs are not requested without a pending source");
+			let pending_blocks = cmp::min(depth, self.chain.lock().pending_blocks) as u64;
+			(block_number + 1..block_number + 1 + pending_blocks).map(|number| Log {
+				block_number: None,
+				transaction_hash: Some(H256::from(number).into()),
+				log_type: "pending".into(),
+				..log(number)
+			}).collect()
+		}
+
+		fn has_pending_source(&self) -> bool {
+			!self.chain.lock().no_pending_source
+		}
+
+		fn removed_logs(&self, _block_hash: H256, _filter: &EthcoreFilter) -> (Vec<Log>, u64) {
+			(Vec::new(), 0)
+		}
+
+		fn polls(&self) -> &SharedFilters {
+			&self.polls
+		}
+
+		fn logs_chunk_size(&self) -> Option<u64> {
+			self.chunk_size
+		}
+
+		fn max_block_range(&self) -> Option<u64> {
+			None
+		}
+
+		fn max_result_size(&self) -> Option<usize> {
+			self.chain.lock().max_result_size
+		}
+
+		fn max_future_blocks(&self) -> Option<u64> {
+			None
+		}
+
+		fn pending_logs_depth(&self) -> usize {
+			self.pending_logs_depth
+		}
+
+		fn pending_transactions_history(&self) -> Option<usize> {
+			None
+		}
+
+		fn logs_timeout(&self) -> Option<&LogsTimeout> {
+			self.logs_timeout.as_ref()
+		}
+	}
+
+	fn io(chunk_size: Option<u64>) -> (IoHandler, Arc<Mutex<TestChain>>) {
+		io_with_timeout(chunk_size, None)
+	}
+
+	fn io_with_timeout(chunk_size: Option<u64>, logs_timeout: Option<LogsTimeout>) -> (IoHandler, Arc<Mutex<TestChain>>) {
+		let chain = Arc::new(Mutex::new(TestChain::default()));
+		let mut io = IoHandler::new();
+		io.extend_with(TestFilterable {
+			chunk_size: chunk_size,
+			logs_timeout: logs_timeout,
+			..filterable_with(chain.clone())
+		}.to_delegate());
+		(io, chain)
+	}
+
+	fn filterable() -> TestFilterable {
+		filterable_with(Arc::new(Mutex::new(TestChain::default())))
+	}
+
+	fn filterable_with(chain: Arc<Mutex<TestChain>>) -> TestFilterable {
+		TestFilterable {
+			chunk_size: None,
+			logs_timeout: None,
+			pending_logs_depth: 1,
+			chain: chain,
+			polls: Arc::new(Mutex::new(PollManager::new())),
+		}
+	}
+
+	fn log(block_number: u64) -> Log {
+		Log {
+			address: Default::default(),
+			topics: vec![],
+			data: Default::default(),
+			block_hash: None,
+			block_number: Some(block_number.into()),
+			transaction_hash: None,
+			transaction_index: None,
+			log_index: None,
+			transaction_log_index: None,
+			log_type: "mined".into(),
+			removed: false,
+		}
+	}
+
+	fn filter_logs(chunk_size: Option<u64>, filter: &str) -> (Vec<String>, Vec<(BlockId, BlockId)>) {
+		let (io, chain) = io(chunk_size);
+
+		let request_filter = format!(r#"{{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}}"#, filter);
+		assert_eq!(io.handle_request_sync(&request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+		let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request_logs).unwrap()).unwrap();
+		let blocks = response["result"].as_array().unwrap().iter()
+			.map(|log| log["blockNumber"].as_str().unwrap().to_owned())
+			.collect();
+
+		let requested = chain.lock().requested_logs.clone();
+		(blocks, requested)
+	}
+
+	#[test]
+	fn should_fetch_logs_in_chunks() {
+		let (blocks, requested) = filter_logs(Some(10), r#"{"fromBlock":"0x5","toBlock":"0x1e"}"#);
+
+		assert_eq!(requested, vec![
+			(BlockId::Number(5), BlockId::Number(14)),
+			(BlockId::Number(15), BlockId::Number(24)),
+			(BlockId::Number(25), BlockId::Number(30)),
+		]);
+		let expected: Vec<String> = (5..31).map(|n| format!("0x{:x}", n)).collect();
+		assert_eq!(blocks, expected);
+	}
+
+	#[test]
+	fn should_limit_logs_after_merging_chunks() {
+		let (blocks, requested) = filter_logs(Some(40), r#"{"fromBlock":"earliest","toBlock":"latest","limit":3}"#);
+
+		assert_eq!(requested, vec![
+			(BlockId::Number(0), BlockId::Number(39)),
+			(BlockId::Number(40), BlockId::Number(79)),
+			(BlockId::Number(80), BlockId::Number(100)),
+		]);
+		assert_eq!(blocks, vec!["0x62".to_owned(), "0x63".to_owned(), "0x64".to_owned()]);
+	}
+
+	#[test]
+	fn should_limit_first_logs_after_merging_chunks() {
+		let (blocks, requested) = filter_logs(Some(40), r#"{"fromBlock":"earliest","toBlock":"latest","limit":3,"limitDirection":"first"}"#);
+
+		assert_eq!(requested.len(), 3);
+		assert_eq!(blocks, vec!["0x0".to_owned(), "0x1".to_owned(), "0x2".to_owned()]);
+	}
+
+	#[test]
+	fn should_stream_the_same_logs_as_buffered_method() {
+		use v1::types::LimitDirection;
+
+		for &chunk_size in &[None, Some(7)] {
+			let filterable = TestFilterable {
+				chunk_size: chunk_size,
+				..filterable()
+			};
+			let filter = |limit: Option<usize>| EthcoreFilter {
+				from_block: BlockId::Number(5),
+				to_block: BlockId::Number(30),
+				address: None,
+				topics: vec![None, None, None, None],
+				limit: limit,
+			};
+
+			let streamed = filterable.logs_stream(filter(None)).collect().wait().unwrap();
+			let (buffered, _) = filterable.logs_with_count(filter(None), None, LimitDirection::Last).wait().unwrap();
+			assert_eq!(streamed.len(), 26);
+			assert_eq!(streamed, buffered);
+
+			for &limit in &[0, 3, 40] {
+				let streamed = filterable.logs_stream(filter(Some(limit))).collect().wait().unwrap();
+				let (buffered, _) = filterable.logs_with_count(filter(None), Some(limit), LimitDirection::Last).wait().unwrap();
+				assert_eq!(streamed, buffered);
+			}
+		}
+	}
+
+	#[test]
+	fn should_fetch_logs_at_once_without_chunk_size() {
+		let (blocks, requested) = filter_logs(None, r#"{"fromBlock":"0x5","toBlock":"0x1e"}"#);
+
+		assert_eq!(requested, vec![(BlockId::Number(5), BlockId::Number(30))]);
+		assert_eq!(blocks.len(), 26);
+	}
+
+	#[test]
+	fn should_count_all_matching_logs_before_limiting() {
+		for &chunk_size in &[None, Some(40)] {
+			let (io, _chain) = io(chunk_size);
+
+			let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x5","toBlock":"latest","limit":3}], "id": 1}"#;
+			assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+			let request_logs = r#"{"jsonrpc": "2.0", "method": "parity_getFilterLogsWithRange", "params": ["0x0"], "id": 1}"#;
+			let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request_logs).unwrap()).unwrap();
+			let blocks: Vec<_> = response["result"]["logs"].as_array().unwrap().iter()
+				.map(|log| log["blockNumber"].as_str().unwrap().to_owned())
+				.collect();
+
+			// one log in each of blocks 5 to 100
+			assert_eq!(response["result"]["totalCount"], "0x60");
+			assert_eq!(blocks, vec!["0x62".to_owned(), "0x63".to_owned(), "0x64".to_owned()]);
+		}
+	}
+
+	#[test]
+	fn should_truncate_large_logs_by_size_before_limit() {
+		let (io, chain) = io(None);
+		{
+			let mut chain = chain.lock();
+			chain.log_data_size = 10_000;
+			chain.max_result_size = Some(50_000);
+		}
+
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x5","toBlock":"latest","limit":5}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let filter_logs = || {
+			let request_logs = r#"{"jsonrpc": "2.0", "method": "parity_getFilterLogsWithRange", "params": ["0x0"], "id": 1}"#;
+			let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request_logs).unwrap()).unwrap();
+			response["result"].clone()
+		};
+
+		// each log takes over 20kB once hex-encoded, so only two of the last five fit
+		let result = filter_logs();
+		let blocks: Vec<_> = result["logs"].as_array().unwrap().iter()
+			.map(|log| log["blockNumber"].as_str().unwrap().to_owned())
+			.collect();
+		assert_eq!(blocks, vec!["0x63".to_owned(), "0x64".to_owned()]);
+		assert_eq!(result["totalCount"], "0x60");
+		assert_eq!(result["truncated"], true);
+
+		chain.lock().max_result_size = None;
+		let result = filter_logs();
+		assert_eq!(result["logs"].as_array().unwrap().len(), 5);
+		assert!(result.get("truncated").is_none());
+	}
+
+	#[test]
+	fn should_not_fetch_logs_of_filters_matching_nothing() {
+		let (io, chain) = io(None);
+		chain.lock().pending_blocks = 2;
+
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x5","toBlock":"pending","topics":[null,[]]}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request), Some(r#"{"jsonrpc":"2.0","result":[],"id":1}"#.to_owned()));
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request), Some(r#"{"jsonrpc":"2.0","result":[],"id":1}"#.to_owned()));
+
+		let chain = chain.lock();
+		assert!(chain.requested_logs.is_empty());
+		assert_eq!(chain.block_hash_calls, 0);
+	}
+
+	#[test]
+	fn should_resolve_block_tags_of_filter_bounds() {
+		let (_, requested) = filter_logs(None, r#"{"fromBlock":"finalized","toBlock":"safe"}"#);
+		assert_eq!(requested, vec![(BlockId::Number(90), BlockId::Number(95))]);
+
+		let (_, requested) = filter_logs(None, r#"{"fromBlock":"earliest","toBlock":"finalized"}"#);
+		assert_eq!(requested, vec![(BlockId::Number(0), BlockId::Number(90))]);
+
+		let (_, requested) = filter_logs(None, r#"{"fromBlock":"safe"}"#);
+		assert_eq!(requested, vec![(BlockId::Number(95), BlockId::Number(100))]);
+	}
+
+	#[test]
+	fn should_scan_logs_from_the_first_block_since_timestamp() {
+		let (io, chain) = io(None);
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"earliest"}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let first_block = |timestamp: u64| {
+			chain.lock().requested_logs.clear();
+			let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_getFilterChangesSinceTimestamp", "params": ["0x0", {}], "id": 1}}"#, timestamp);
+			let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(&request).unwrap()).unwrap();
+			let requested = chain.lock().requested_logs.clone();
+			assert_eq!(requested.len(), 1);
+			response["result"]["fromBlock"].as_str().unwrap().to_owned()
+		};
+
+		assert_eq!(first_block(0), "0x0");
+		// exactly the timestamp of block 5
+		assert_eq!(first_block(1_075), "0x5");
+		assert_eq!(first_block(1_076), "0x6");
+		// within the pause after block 50
+		assert_eq!(first_block(1_800), "0x33");
+		assert_eq!(first_block(1_000 + 100 * 15 + 100), "0x64");
+		// newer than all blocks
+		assert_eq!(first_block(1_000 + 100 * 15 + 101), "0x65");
+	}
+
+	#[test]
+	fn should_time_out_stalled_logs_requests() {
+		let (io, chain) = io_with_timeout(None, Some(LogsTimeout::new(Duration::from_millis(10))));
+		chain.lock().stalled = true;
+
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x5"}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let response = r#"{"jsonrpc":"2.0","error":{"code":-32043,"message":"Request has timed out."},"id":1}"#;
+		let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_logs), Some(response.to_owned()));
+		let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_changes), Some(response.to_owned()));
+	}
+
+	#[test]
+	fn should_poll_the_same_blocks_again_after_failed_poll() {
+		let (io, chain) = io_with_timeout(None, Some(LogsTimeout::new(Duration::from_millis(10))));
+
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x5"}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		chain.lock().stalled = true;
+		let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+		let response = r#"{"jsonrpc":"2.0","error":{"code":-32043,"message":"Request has timed out."},"id":1}"#;
+		assert_eq!(io.handle_request_sync(request_changes), Some(response.to_owned()));
+
+		{
+			let mut chain = chain.lock();
+			chain.stalled = false;
+			chain.best_block = 102;
+		}
+		io.handle_request_sync(request_changes).unwrap();
+		io.handle_request_sync(request_changes).unwrap();
+		assert_eq!(chain.lock().requested_logs, vec![
+			(BlockId::Number(100), BlockId::Latest),
+			(BlockId::Number(100), BlockId::Latest),
+			(BlockId::Number(103), BlockId::Latest),
+		]);
+	}
+
+	#[test]
+	fn should_keep_throttled_filter_installed_when_drained() {
+		let filterable = filterable();
+		filterable.polls.lock().set_min_poll_interval(Duration::from_secs(60));
+		let index = || serde_json::from_str::<Index>(r#""0x0""#).unwrap();
+
+		filterable.new_block_filter(None.into()).unwrap();
+		filterable.filter_changes(index()).wait().unwrap();
+
+		assert_eq!(filterable.drain_filter(index()).wait(), Err(errors::filter_polled_too_often()));
+		assert!(filterable.polls.lock().peek(&0).is_some());
+	}
+
+	#[test]
+	fn should_keep_filter_installed_when_drain_times_out() {
+		let filterable = TestFilterable {
+			logs_timeout: Some(LogsTimeout::new(Duration::from_millis(10))),
+			..filterable()
+		};
+		let index = || serde_json::from_str::<Index>(r#""0x0""#).unwrap();
+
+		let filter = serde_json::from_str(r#"{"fromBlock":"0x5"}"#).unwrap();
+		filterable.new_filter(filter).unwrap();
+
+		filterable.chain.lock().stalled = true;
+		assert_eq!(filterable.drain_filter(index()).wait(), Err(errors::request_timeout()));
+		assert!(filterable.polls.lock().peek(&0).is_some());
+
+		// the same blocks are drained again
+		filterable.chain.lock().stalled = false;
+		assert_eq!(filterable.drain_filter(index()).wait(), Ok(FilterChanges::Logs(Vec::new())));
+		assert_eq!(filterable.drain_filter(index()).wait(), Err(errors::filter_not_found()));
+		assert_eq!(filterable.chain.lock().requested_logs, vec![
+			(BlockId::Number(100), BlockId::Latest),
+			(BlockId::Number(100), BlockId::Latest),
+		]);
+	}
+
+	#[test]
+	fn should_return_logs_completed_within_timeout() {
+		let (io, _chain) = io_with_timeout(None, Some(LogsTimeout::new(Duration::from_secs(60))));
+
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x5","toBlock":"0x6"}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+		let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request_logs).unwrap()).unwrap();
+		assert_eq!(response["result"].as_array().unwrap().len(), 2);
+	}
+
+	#[test]
+	fn should_poll_filters_of_on_demand_backends() {
+		let filterable = filterable();
+		filterable.chain.lock().on_demand = true;
+		let index = |id: RpcU256| serde_json::from_value::<Index>(serde_json::to_value(id).unwrap()).unwrap();
+
+		let block_filter = filterable.new_block_filter(None.into()).unwrap();
+		let log_filter = filterable.new_filter(serde_json::from_str(r#"{"fromBlock":"0x5","toBlock":"0x6"}"#).unwrap()).unwrap();
+		filterable.chain.lock().best_block = 102;
+
+		let hashes = FilterChanges::Hashes(vec![H256::from(101).into(), H256::from(102).into()]);
+		assert_eq!(filterable.filter_changes(index(block_filter)).wait(), Ok(hashes));
+
+		// the filter moves on once the logs arrive
+		filterable.filter_changes(index(log_filter)).wait().unwrap();
+		filterable.filter_changes(index(log_filter)).wait().unwrap();
+		assert_eq!(filterable.chain.lock().requested_logs, vec![
+			(BlockId::Number(100), BlockId::Latest),
+			(BlockId::Number(103), BlockId::Latest),
+		]);
+		assert_eq!(filterable.filter_logs(index(log_filter)).wait().map(|logs| logs.len()), Ok(2));
+	}
+
+	#[test]
+	fn should_share_criteria_of_identical_filters() {
+		let filterable = filterable();
+
+		let filter = |s: &str| serde_json::from_str(s).unwrap();
+		assert_eq!(filterable.new_filter(filter(r#"{"fromBlock":"0x5"}"#)), Ok(0.into()));
+		assert_eq!(filterable.new_filter(filter(r#"{"fromBlock":"0x5"}"#)), Ok(1.into()));
+		assert_eq!(filterable.new_filter(filter(r#"{"fromBlock":"0x6"}"#)), Ok(2.into()));
+
+		let criteria = |id: usize| filterable.polls.lock().poll(&id).unwrap().modify(|filter| match *filter {
+			PollFilter::Logs { ref filter, .. } => filter.clone(),
+			_ => unreachable!(),
+		});
+		assert!(Arc::ptr_eq(&criteria(0), &criteria(1)));
+		assert!(!Arc::ptr_eq(&criteria(0), &criteria(2)));
+
+		// each filter moves its own cursor
+		filterable.chain.lock().best_block = 101;
+		for id in &[r#""0x0""#, r#""0x0""#, r#""0x1""#] {
+			filterable.filter_changes(serde_json::from_str(id).unwrap()).wait().unwrap();
+		}
+		assert_eq!(filterable.chain.lock().requested_logs, vec![
+			(BlockId::Number(100), BlockId::Latest),
+			(BlockId::Number(102), BlockId::Latest),
+			(BlockId::Number(100), BlockId::Latest),
+		]);
+	}
+
+	#[test]
+	fn should_share_criteria_without_locking_installed_filters() {
+		let filterable = filterable();
+
+		let filter = |s: &str| serde_json::from_str(s).unwrap();
+		assert_eq!(filterable.new_filter(filter(r#"{"fromBlock":"0x5"}"#)), Ok(0.into()));
+
+		// e.g. while the first filter is being polled
+		let installed = filterable.polls.lock().poll(&0).unwrap().clone();
+		installed.modify(|_| {
+			assert_eq!(filterable.new_filter(filter(r#"{"fromBlock":"0x5"}"#)), Ok(1.into()));
+		});
+	}
+
+	#[test]
+	fn should_poll_distinct_filters_concurrently() {
+		let filterable = Arc::new(filterable());
+		let first = filterable.new_block_filter(None.into()).unwrap();
+		filterable.chain.lock().best_block = 102;
+		let second = filterable.new_block_filter(None.into()).unwrap();
+		filterable.chain.lock().best_block = 103;
+
+		let poll = |id: RpcU256| {
+			let filterable = filterable.clone();
+			let index: Index = serde_json::from_value(serde_json::to_value(id).unwrap()).unwrap();
+			thread::spawn(move || filterable.filter_changes(index).wait().unwrap())
+		};
+		let hashes = |numbers: &[u64]| FilterChanges::Hashes(numbers.iter().map(|&n| H256::from(n).into()).collect());
+
+		// the second filter is polled from another thread while the first one is being polled
+		let first_filter = filterable.polls.lock().poll(&0).unwrap().clone();
+		let second_changes = first_filter.modify(|_| poll(second).join().unwrap());
+		let first_changes = poll(first).join().unwrap();
+
+		assert_eq!(first_changes, hashes(&[101, 102, 103]));
+		assert_eq!(second_changes, hashes(&[103]));
+	}
+
+	#[test]
+	fn should_reject_concurrent_polls_of_the_same_filter() {
+		let filterable = Arc::new(filterable());
+		let index = || serde_json::from_str::<Index>(r#""0x0""#).unwrap();
+		filterable.new_filter(serde_json::from_str(r#"{"fromBlock":"0x5"}"#).unwrap()).unwrap();
+		filterable.chain.lock().best_block = 101;
+
+		// the filter is polled from another thread while its first poll is in flight
+		let first = filterable.filter_changes(index());
+		let second = {
+			let filterable = filterable.clone();
+			let index = index();
+			thread::spawn(move || filterable.filter_changes(index).wait()).join().unwrap()
+		};
+		assert_eq!(second, Err(errors::filter_polled_too_often()));
+		first.wait().unwrap();
+
+		// a dropped poll doesn't move the filter, nor keeps it from being polled
+		drop(filterable.filter_changes(index()));
+		filterable.filter_changes(index()).wait().unwrap();
+		assert_eq!(filterable.chain.lock().requested_logs, vec![
+			(BlockId::Number(100), BlockId::Latest),
+			(BlockId::Number(102), BlockId::Latest),
+			(BlockId::Number(102), BlockId::Latest),
+		]);
+	}
+
+	#[test]
+	fn should_carry_forward_older_pending_transactions_beyond_limit() {
+		let (io, chain) = io(None);
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "params": [{"limit":2,"newestFirst":true}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		// more transactions than the limit are queued between polls
+		chain.lock().pending_transactions = (1..6).map(H256::from).collect();
+		let hashes = |numbers: &[u64]| numbers.iter().map(|&n| format!("0x{:x}", H256::from(n))).collect::<Vec<_>>();
+
+		assert_eq!(block_filter_changes(&io), hashes(&[5, 4]));
+		assert_eq!(block_filter_changes(&io), hashes(&[3, 2]));
+		assert_eq!(block_filter_changes(&io), hashes(&[1]));
+		assert!(block_filter_changes(&io).is_empty());
+	}
+
+	#[test]
+	fn should_flag_pending_transactions_returned_by_reorg() {
+		let (io, chain) = io(None);
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "params": [{"markReturned":true}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+		let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+		let changes = |entries: &[(u64, bool)]| format!(r#"{{"jsonrpc":"2.0","result":[{}],"id":1}}"#, entries.iter()
+			.map(|&(n, returned)| format!(r#"{{"hash":"0x{:x}","returned":{}}}"#, H256::from(n), returned))
+			.collect::<Vec<_>>()
+			.join(","));
+
+		chain.lock().pending_transactions = vec![H256::from(1)];
+		assert_eq!(io.handle_request_sync(request_changes), Some(changes(&[(1, false)])));
+
+		// the transaction is mined
+		{
+			let mut chain = chain.lock();
+			chain.pending_transactions = Vec::new();
+			chain.mined_transactions = vec![H256::from(1)];
+		}
+		assert_eq!(io.handle_request_sync(request_changes), Some(changes(&[])));
+
+		// the block is retracted, the transaction comes back along with a fresh one
+		{
+			let mut chain = chain.lock();
+			chain.pending_transactions = vec![H256::from(1), H256::from(2)];
+			chain.mined_transactions = Vec::new();
+		}
+		assert_eq!(io.handle_request_sync(request_changes), Some(changes(&[(1, true), (2, false)])));
+		assert_eq!(io.handle_request_sync(request_changes), Some(changes(&[])));
+	}
+
+	fn block_filter_changes(io: &IoHandler) -> Vec<String> {
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+		let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+		response["result"].as_array().unwrap().iter()
+			.map(|hash| hash.as_str().unwrap().to_owned())
+			.collect()
+	}
+
+	#[test]
+	fn should_report_each_block_once() {
+		let (io, chain) = io(None);
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		// two blocks arrive between polls
+		chain.lock().best_block = 102;
+		assert_eq!(block_filter_changes(&io), vec![
+			format!("0x{:x}", H256::from(101)),
+			format!("0x{:x}", H256::from(102)),
+		]);
+		// no new blocks, the best block is not reported again
+		assert!(block_filter_changes(&io).is_empty());
+
+		chain.lock().best_block = 103;
+		assert_eq!(block_filter_changes(&io), vec![format!("0x{:x}", H256::from(103))]);
+		assert!(block_filter_changes(&io).is_empty());
+	}
+
+	#[test]
+	fn should_report_blocks_with_unresolved_hashes_later() {
+		let (io, chain) = io(None);
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		{
+			let mut chain = chain.lock();
+			chain.best_block = 103;
+			chain.missing_block = Some(102);
+		}
+		assert_eq!(block_filter_changes(&io), vec![format!("0x{:x}", H256::from(101))]);
+
+		chain.lock().missing_block = None;
+		assert_eq!(block_filter_changes(&io), vec![
+			format!("0x{:x}", H256::from(102)),
+			format!("0x{:x}", H256::from(103)),
+		]);
+		assert!(block_filter_changes(&io).is_empty());
+	}
+
+	#[test]
+	fn should_report_unresolved_headers_later() {
+		let (io, chain) = io(None);
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockHeaderFilter", "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+		let header_numbers = || {
+			let request = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+			let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+			response["result"].as_array().unwrap().iter()
+				.map(|header| header["number"].as_str().unwrap().to_owned())
+				.collect::<Vec<_>>()
+		};
+
+		{
+			let mut chain = chain.lock();
+			chain.best_block = 103;
+			chain.missing_block = Some(102);
+		}
+		assert_eq!(header_numbers(), vec!["0x65"]);
+
+		chain.lock().missing_block = None;
+		assert_eq!(header_numbers(), vec!["0x66", "0x67"]);
+		assert!(header_numbers().is_empty());
+	}
+
+	#[test]
+	fn should_forget_pending_logs_once_excluded() {
+		use v1::types::{BlockNumber, Filter};
+
+		let chain = Arc::new(Mutex::new(TestChain::default()));
+		let filterable = filterable_with(chain);
+		let filter: Filter = serde_json::from_str(r#"{"toBlock":"pending"}"#).unwrap();
+		let id = filterable.new_filter(filter).unwrap();
+		let index: Index = serde_json::from_value(serde_json::to_value(id).unwrap()).unwrap();
+		let poll_id = index.value();
+		let previous_logs = move |filterable: &TestFilterable| filterable.polls.lock().poll(&poll_id).unwrap().modify(|filter| match *filter {
+			PollFilter::Logs { ref previous_logs, .. } => previous_logs.len(),
+			_ => panic!("log filter is installed"),
+		});
+
+		// pretend a pending log has been reported
+		filterable.polls.lock().poll_mut(&poll_id).unwrap().modify(|filter| if let PollFilter::Logs { ref mut previous_logs, .. } = *filter {
+			previous_logs.insert((None, None, None, 0));
+		});
+		filterable.filter_changes(index.clone()).wait().unwrap();
+		// no pending logs anymore
+		assert_eq!(previous_logs(&filterable), 0);
+
+		filterable.polls.lock().poll_mut(&poll_id).unwrap().modify(|filter| if let PollFilter::Logs { ref mut previous_logs, ref mut filter, .. } = *filter {
+			previous_logs.insert((None, None, None, 0));
+			let mut latest = (**filter).clone();
+			latest.to_block = Some(BlockNumber::Latest);
+			*filter = Arc::new(latest);
+		});
+		filterable.filter_changes(index).wait().unwrap();
+		assert_eq!(previous_logs(&filterable), 0);
+	}
+
+	#[test]
+	fn should_fail_cleanly_if_stored_filter_cant_be_converted() {
+		use v1::types::Filter;
+
+		let filterable = filterable();
+		let filter: Filter = serde_json::from_str(r#"{"fromBlock":"0x5"}"#).unwrap();
+		let id = filterable.new_filter(filter).unwrap();
+		let index: Index = serde_json::from_value(serde_json::to_value(id).unwrap()).unwrap();
+
+		// pretend an invalid filter slipped through validation
+		filterable.polls.lock().poll_mut(&index.value()).unwrap().modify(|filter| if let PollFilter::Logs { ref mut filter, .. } = *filter {
+			*filter = Arc::new(serde_json::from_str(r#"{"topics":[null,null,null,null,null]}"#).unwrap());
+		});
+
+		let changes = filterable.filter_changes(index.clone()).wait().unwrap_err();
+		assert_eq!(changes.code, ErrorCode::InvalidParams);
+		let logs = filterable.filter_logs(index).wait().unwrap_err();
+		assert_eq!(logs.code, ErrorCode::InvalidParams);
+	}
+
+	#[test]
+	fn should_include_logs_of_pending_blocks_up_to_depth() {
+		use v1::types::Filter;
+
+		let pending_logs = |depth: usize| {
+			let chain = io(None).1;
+			chain.lock().pending_blocks = 2;
+			let filterable = TestFilterable {
+				pending_logs_depth: depth,
+				..filterable_with(chain)
+			};
+			let filter: Filter = serde_json::from_str(r#"{"toBlock":"pending"}"#).unwrap();
+			let id = filterable.new_filter(filter).unwrap();
+			let index: Index = serde_json::from_value(serde_json::to_value(id).unwrap()).unwrap();
+			match filterable.filter_changes(index).wait().unwrap() {
+				FilterChanges::Logs(logs) => logs.into_iter()
+					.filter(|log| log.log_type == "pending")
+					.map(|log| log.transaction_hash)
+					.collect::<Vec<_>>(),
+				_ => panic!("log filter returns logs"),
+			}
+		};
+
+		let hash = |number: u64| -> Option<RpcH256> { Some(H256::from(number).into()) };
+		assert_eq!(pending_logs(1), vec![hash(101)]);
+		assert_eq!(pending_logs(2), vec![hash(101), hash(102)]);
+		// there are no more pending blocks
+		assert_eq!(pending_logs(3), vec![hash(101), hash(102)]);
+	}
+
+	#[test]
+	fn should_name_malformed_address_of_new_filter() {
+		let (io, _) = io(None);
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"address":["0x0000000000000000000000000000000000000005","0x05"]}], "id": 1}"#;
+		let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+
+		assert_eq!(response["error"]["code"], -32602);
+		assert!(response["error"]["message"].as_str().unwrap().contains(r#""0x05""#));
+	}
+
+	#[test]
+	fn should_reject_nested_topic_arrays_of_new_filter() {
+		let (io, _) = io(None);
+		let topic = "0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b";
+		let request = |topics: String| format!(r#"{{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{{"topics":{}}}], "id": 1}}"#, topics);
+
+		// a flat array is an OR-set of topics in its position
+		let flat = request(format!(r#"[null,["{}","{}"]]"#, topic, topic));
+		assert_eq!(io.handle_request_sync(&flat), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let nested = request(format!(r#"[null,[["{}"],"{}"]]"#, topic, topic));
+		let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(&nested).unwrap()).unwrap();
+		assert_eq!(response["error"]["code"], -32602);
+		assert!(response["error"]["message"].as_str().unwrap().contains("flat array"));
+	}
+
+	#[test]
+	fn should_skip_pending_logs_without_pending_source() {
+		let (io, chain) = io(None);
+		{
+			let mut chain = chain.lock();
+			chain.pending_blocks = 1;
+			chain.no_pending_source = true;
+		}
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"latest","toBlock":"pending"}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let logs = |method: &str| {
+			let request = format!(r#"{{"jsonrpc": "2.0", "method": "{}", "params": ["0x0"], "id": 1}}"#, method);
+			let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(&request).unwrap()).unwrap();
+			let logs = match method {
+				"parity_getFilterLogsWithRange" => response["result"]["logs"].clone(),
+				_ => response["result"].clone(),
+			};
+			logs.as_array().expect("logs are returned without error").iter()
+				.map(|log| log["type"].as_str().unwrap().to_owned())
+				.collect::<Vec<_>>()
+		};
+
+		chain.lock().best_block = 101;
+		assert!(logs("eth_getFilterChanges").iter().all(|log_type| log_type == "mined"));
+		assert_eq!(logs("parity_getFilterLogsWithRange"), vec!["mined".to_owned()]);
+	}
+
+	#[test]
+	fn should_not_query_block_hashes_without_new_blocks() {
+		let (io, chain) = io(None);
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		chain.lock().best_block = 102;
+		assert_eq!(block_filter_changes(&io), vec![
+			format!("0x{:x}", H256::from(101)),
+			format!("0x{:x}", H256::from(102)),
+		]);
+
+		chain.lock().block_hash_calls = 0;
+		assert!(block_filter_changes(&io).is_empty());
+		assert!(block_filter_changes(&io).is_empty());
+		assert_eq!(chain.lock().block_hash_calls, 0);
+	}
+
+	#[test]
+	fn should_report_blocks_replaced_by_reorganisation() {
+		let (io, chain) = io(None);
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		chain.lock().best_block = 102;
+		assert_eq!(block_filter_changes(&io).len(), 2);
+
+		// same height, different best block
+		{
+			let mut chain = chain.lock();
+			chain.reorged_from = 102;
+			chain.fork = 1;
+		}
+		assert_eq!(block_filter_changes(&io), vec![format!("0x{:x}", H256::from(1_000_102))]);
+		assert!(block_filter_changes(&io).is_empty());
+
+		// reorganisation replacing already reported blocks and extending the chain
+		{
+			let mut chain = chain.lock();
+			chain.reorged_from = 101;
+			chain.fork = 2;
+			chain.best_block = 103;
+		}
+		assert_eq!(block_filter_changes(&io), vec![
+			format!("0x{:x}", H256::from(2_000_101)),
+			format!("0x{:x}", H256::from(2_000_102)),
+			format!("0x{:x}", H256::from(2_000_103)),
+		]);
+	}
+
+	#[test]
+	fn should_not_overflow_at_max_block_number() {
+		let (io, chain) = io(None);
+		chain.lock().best_block = u64::MAX;
+
+		let requests = [
+			r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#,
+			r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "id": 1}"#,
+			r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#,
+		];
+		for (id, request) in requests.iter().enumerate() {
+			let response = format!(r#"{{"jsonrpc":"2.0","result":"0x{}","id":1}}"#, id);
+			assert_eq!(io.handle_request_sync(request), Some(response));
+		}
+
+		for id in 0..3 {
+			for _ in 0..2 {
+				let request = format!(r#"{{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x{}"], "id": 1}}"#, id);
+				assert_eq!(io.handle_request_sync(&request), Some(r#"{"jsonrpc":"2.0","result":[],"id":1}"#.to_owned()));
+			}
+		}
+	}
+
+	#[test]
+	fn should_stream_filter_changes_until_uninstalled() {
+		let chain = Arc::new(Mutex::new(TestChain::default()));
+		let filterable = Arc::new(filterable_with(chain.clone()));
+		let id = filterable.new_block_filter(None.into()).unwrap();
+		let index: Index = serde_json::from_value(serde_json::to_value(id).unwrap()).unwrap();
+
+		let (ticks, clock) = mpsc::unbounded();
+		let mut stream = FilterChangeStream::new(filterable.clone(), index.clone(), clock).wait();
+
+		ticks.unbounded_send(()).unwrap();
+		assert_eq!(stream.next(), Some(Ok(FilterChanges::Hashes(vec![]))));
+
+		chain.lock().best_block = 102;
+		ticks.unbounded_send(()).unwrap();
+		assert_eq!(stream.next(), Some(Ok(FilterChanges::Hashes(vec![
+			H256::from(101).into(),
+			H256::from(102).into(),
+		]))));
+
+		assert!(filterable.uninstall_filter(index).unwrap());
+		ticks.unbounded_send(()).unwrap();
+		assert_eq!(stream.next(), None);
+	}
+
+	#[test]
+	fn should_return_the_same_logs_with_topic_lookup() {
+		let fetch = |topic_index: bool, chunk_size: Option<u64>, filter: &str| {
+			let (io, chain) = io(chunk_size);
+			chain.lock().topic_index = topic_index;
+
+			let request_filter = format!(r#"{{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}}"#, filter);
+			io.handle_request_sync(&request_filter).unwrap();
+			let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+			let logs = io.handle_request_sync(request_logs).unwrap();
+
+			chain.lock().best_block = 102;
+			let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+			let changes = io.handle_request_sync(request_changes).unwrap();
+
+			let calls = chain.lock().topic_logs_calls;
+			(logs, changes, calls)
+		};
+
+		let any_address = r#"{"fromBlock":"0x5a","topics":["0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b"]}"#;
+		for &chunk_size in &[None, Some(3)] {
+			let (fallback_logs, fallback_changes, fallback_calls) = fetch(false, chunk_size, any_address);
+			let (logs, changes, calls) = fetch(true, chunk_size, any_address);
+			assert_eq!(logs, fallback_logs);
+			assert_eq!(changes, fallback_changes);
+			assert_eq!(fallback_calls, 0);
+			assert!(calls > 0);
+		}
+
+		let (_, _, calls) = fetch(true, None, r#"{"fromBlock":"0x5a","address":"0x0000000000000000000000000000000000000001"}"#);
+		assert_eq!(calls, 0);
+	}
+
+	#[test]
+	fn should_return_changes_since_given_block() {
+		let (io, chain) = io(None);
+		let changes_since = |since: &str| {
+			let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_getFilterChangesSince", "params": ["0x0", "{}"], "id": 1}}"#, since);
+			let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(&request).unwrap()).unwrap();
+			response["result"]["logs"].as_array().unwrap().iter()
+				.map(|log| log["blockNumber"].as_str().unwrap().to_owned())
+				.collect::<Vec<_>>()
+		};
+
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x62"}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+		assert_eq!(changes_since("0x64"), vec!["0x64"]);
+
+		// a reconnecting client gets everything since the block it knows
+		chain.lock().best_block = 102;
+		assert_eq!(changes_since("0x64"), vec!["0x64", "0x65", "0x66"]);
+		assert_eq!(changes_since("latest"), vec!["0x66"]);
+
+		// the filter's own range still applies
+		assert_eq!(changes_since("earliest"), vec!["0x62", "0x63", "0x64", "0x65", "0x66"]);
+		assert_eq!(changes_since("0x70"), Vec::<String>::new());
+	}
+
+	#[test]
+	fn should_return_safe_and_finalized_blocks() {
+		let (io, chain) = io(None);
+		chain.lock().best_block = 120;
+		let block_number = |method: &str| {
+			let request = format!(r#"{{"jsonrpc": "2.0", "method": "{}", "params": [], "id": 1}}"#, method);
+			let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(&request).unwrap()).unwrap();
+			response["result"]["number"].as_str().unwrap().to_owned()
+		};
+
+		assert_eq!(block_number("eth_getSafeBlock"), "0x73");
+		assert_eq!(block_number("eth_getFinalizedBlock"), "0x6e");
+	}
+
+	#[test]
+	fn should_test_filter_against_given_log() {
+		let (io, _) = io(None);
+		let log = r#"{"address":"0x0000000000000000000000000000000000000001","topics":[],"data":"0x","blockNumber":"0x5a"}"#;
+		let request = |filter: &str| format!(r#"{{"jsonrpc": "2.0", "method": "parity_testFilter", "params": [{}, {}], "id": 1}}"#, filter, log);
+
+		assert_eq!(io.handle_request_sync(&request(r#"{"address":"0x0000000000000000000000000000000000000001","fromBlock":"finalized"}"#)), Some(r#"{"jsonrpc":"2.0","result":true,"id":1}"#.to_owned()));
+		assert_eq!(io.handle_request_sync(&request(r#"{"fromBlock":"safe"}"#)), Some(r#"{"jsonrpc":"2.0","result":false,"id":1}"#.to_owned()));
+		assert_eq!(io.handle_request_sync(&request(r#"{"address":"0x0000000000000000000000000000000000000002"}"#)), Some(r#"{"jsonrpc":"2.0","result":false,"id":1}"#.to_owned()));
+	}
+
+	#[test]
+	fn should_resume_imported_filter_from_exported_cursor() {
+		let (restarted, restarted_chain) = io(None);
+		let chain = io(None).1;
+		let filterable = filterable_with(chain.clone());
+		let polls = filterable.polls.clone();
+		let mut io = IoHandler::new();
+		io.extend_with(filterable.to_delegate());
+		let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x5","address":"0x0000000000000000000000000000000000000001"}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+		let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+
+		chain.lock().best_block = 102;
+		io.handle_request_sync(request_changes).unwrap();
+		assert_eq!(chain.lock().requested_logs, vec![(BlockId::Number(100), BlockId::Latest)]);
+
+		let index: Index = serde_json::from_value(serde_json::to_value(0).unwrap()).unwrap();
+		let state = serde_json::to_value(export_filter(&polls, index).unwrap()).unwrap();
+		assert_eq!(state["type"], "logs");
+		assert_eq!(state["nextBlock"], "0x67");
+		assert_eq!(state["filter"]["fromBlock"], "0x5");
+
+		// the node restarts with no filters installed
+		restarted_chain.lock().best_block = 105;
+		let request_import = format!(r#"{{"jsonrpc": "2.0", "method": "parity_importFilter", "params": [{}], "id": 1}}"#, state);
+		assert_eq!(restarted.handle_request_sync(&request_import), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		restarted.handle_request_sync(request_changes).unwrap();
+		assert_eq!(restarted_chain.lock().requested_logs, vec![(BlockId::Number(103), BlockId::Latest)]);
+	}
+
+	#[test]
+	fn should_reject_imported_cursor_ahead_of_node() {
+		let (io, _) = io(None);
+		let request = r#"{"jsonrpc": "2.0", "method": "parity_importFilter", "params": [{"type":"blockHeaders","nextBlock":"0x65"}], "id": 1}"#;
+		assert_eq!(io.handle_request_sync(request), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+		let request = r#"{"jsonrpc": "2.0", "method": "parity_importFilter", "params": [{"type":"blockHeaders","nextBlock":"0x66"}], "id": 1}"#;
+		let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+		assert_eq!(response["error"]["code"], -32602);
+	}
+
+	#[test]
+	fn should_return_no_finalized_block_without_finality_data() {
+		let client = Arc::new(TestBlockChainClient::new());
+		client.add_blocks(3, EachBlockWith::Nothing);
+		let filterable = EthFilterClient::new(client, Arc::new(TestMinerService::default()));
+
+		assert_eq!(filterable.safe_block_number(), None);
+		assert_eq!(filterable.safe_block().unwrap(), None);
+		assert_eq!(filterable.finalized_block().unwrap(), None);
+	}
+
+	#[test]
+	fn should_tell_canonical_blocks_from_orphaned_ones() {
+		let client = Arc::new(TestBlockChainClient::new());
+		client.add_blocks(3, EachBlockWith::Nothing);
+		let filterable = EthFilterClient::new(client.clone(), Arc::new(TestMinerService::default()));
+
+		// another block at the height of the canonical block 2
+		let canonical = client.numbers.read()[&2];
+		let mut header = Header::new();
+		header.set_number(2);
+		header.set_parent_hash(client.numbers.read()[&1]);
+		header.set_extra_data(b"orphan".to_vec());
+		let mut block = RlpStream::new_list(3);
+		block.append(&header);
+		block.begin_list(0);
+		block.begin_list(0);
+		let orphaned = client.import_block(block.out()).unwrap();
+
+		assert!(orphaned != canonical);
+		assert!(filterable.is_canonical(canonical.into()));
+		assert!(!filterable.is_canonical(orphaned.into()));
+		assert!(!filterable.is_canonical(H256::from(0x42).into()));
 	}
 }
@@ -17,47 +17,55 @@
 //! Eth PUB-SUB rpc implementation.
 
 use std::sync::{Arc, Weak};
-use std::collections::BTreeMap;
-use rustc_hex::ToHex;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use jsonrpc_core::{BoxFuture, Result, Error};
 use jsonrpc_core::futures::{self, Future, IntoFuture};
 use jsonrpc_macros::Trailing;
 use jsonrpc_macros::pubsub::{Sink, Subscriber};
 use jsonrpc_pubsub::SubscriptionId;
+use futures_cpupool::CpuPool;
 
-use v1::helpers::{errors, limit_logs, Subscribers};
+use v1::helpers::{errors, HashHistory, limit_logs, limit_size, replay_block_return_data, compress_return_data, encode_return_data, Subscribers};
 use v1::helpers::light_fetch::LightFetch;
 use v1::metadata::Metadata;
 use v1::traits::EthPubSub;
-use v1::types::{pubsub, RichHeader, Log};
+use v1::types::{pubsub, Filter, LimitDirection, RichHeader, Log, Transaction};
 
 use ethcore::encoded;
 use ethcore::filter::Filter as EthFilter;
-use ethcore::client::{BlockChainClient, ChainNotify, BlockId, TransactionId, CallAnalytics};
+use ethcore::client::{BlockChainClient, ChainNotify, BlockId};
 use ethsync::LightSync;
 use light::cache::Cache;
 use light::on_demand::OnDemand;
 use light::client::{LightChainClient, LightChainNotify};
 use parity_reactor::Remote;
-use ethereum_types::H256;
+use ethereum_types::{Address, H256};
 use bytes::Bytes;
+use transaction::Action;
 use parking_lot::{RwLock, Mutex};
 
 type Client = Sink<pubsub::Result>;
 
+/// Number of recently notified pending transactions remembered to avoid notifying them again.
+const NOTIFIED_TRANSACTIONS_HISTORY: usize = 4096;
+
+/// Number of return data entries notified for a single block if the subscription doesn't set it.
+const DEFAULT_RETURN_DATA_PER_BLOCK: usize = 256;
+
 /// Eth PubSub implementation.
 pub struct EthPubSubClient<C> {
 	handler: Arc<ChainNotificationHandler<C>>,
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
-	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
-	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
-	return_data_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	logs_subscribers: Arc<RwLock<Subscribers<(Client, Filter)>>>,
+	transactions_subscribers: Arc<RwLock<Subscribers<(Client, bool)>>>,
+	return_data_subscribers: Arc<RwLock<Subscribers<(Client, pubsub::ReturnDataOptions)>>>,
+	full_transactions: bool,
 }
 
 impl<C> EthPubSubClient<C> {
 	/// Creates new `EthPubSubClient`.
-	pub fn new(client: Arc<C>, remote: Remote) -> Self {
+	pub fn new(client: Arc<C>, remote: Remote, pool: CpuPool) -> Self {
 		let heads_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let logs_subscribers = Arc::new(RwLock::new(Subscribers::default()));
 		let transactions_subscribers = Arc::new(RwLock::new(Subscribers::default()));
@@ -67,22 +75,25 @@ impl<C> EthPubSubClient<C> {
 			handler: Arc::new(ChainNotificationHandler {
 				client,
 				remote,
+				pool,
 				heads_subscribers: heads_subscribers.clone(),
 				logs_subscribers: logs_subscribers.clone(),
 				transactions_subscribers: transactions_subscribers.clone(),
+				notified_transactions: Default::default(),
 				return_data_subscribers: return_data_subscribers.clone(),
 			}),
 			heads_subscribers,
 			logs_subscribers,
 			transactions_subscribers,
 			return_data_subscribers,
+			full_transactions: true,
 		}
 	}
 
 	/// Creates new `EthPubSubClient` with deterministic subscription ids.
 	#[cfg(test)]
 	pub fn new_test(client: Arc<C>, remote: Remote) -> Self {
-		let client = Self::new(client, remote);
+		let client = Self::new(client, remote, CpuPool::new(1));
 		*client.heads_subscribers.write() = Subscribers::new_test();
 		*client.logs_subscribers.write() = Subscribers::new_test();
 		*client.transactions_subscribers.write() = Subscribers::new_test();
@@ -104,6 +115,7 @@ impl EthPubSubClient<LightFetch> {
 		sync: Arc<LightSync>,
 		cache: Arc<Mutex<Cache>>,
 		remote: Remote,
+		pool: CpuPool,
 		gas_price_percentile: usize,
 	) -> Self {
 		let fetch = LightFetch {
@@ -113,7 +125,11 @@ impl EthPubSubClient<LightFetch> {
 			cache,
 			gas_price_percentile,
 		};
-		EthPubSubClient::new(Arc::new(fetch), remote)
+		EthPubSubClient {
+			// the light transaction queue is not available to the handler
+			full_transactions: false,
+			..EthPubSubClient::new(Arc::new(fetch), remote, pool)
+		}
 	}
 }
 
@@ -121,10 +137,12 @@ impl EthPubSubClient<LightFetch> {
 pub struct ChainNotificationHandler<C> {
 	client: Arc<C>,
 	remote: Remote,
+	pool: CpuPool,
 	heads_subscribers: Arc<RwLock<Subscribers<Client>>>,
-	logs_subscribers: Arc<RwLock<Subscribers<(Client, EthFilter)>>>,
-	transactions_subscribers: Arc<RwLock<Subscribers<Client>>>,
-	return_data_subscribers: Arc<RwLock<Subscribers<Client>>>,
+	logs_subscribers: Arc<RwLock<Subscribers<(Client, Filter)>>>,
+	transactions_subscribers: Arc<RwLock<Subscribers<(Client, bool)>>>,
+	notified_transactions: Mutex<HashHistory>,
+	return_data_subscribers: Arc<RwLock<Subscribers<(Client, pubsub::ReturnDataOptions)>>>,
 }
 
 impl<C> ChainNotificationHandler<C> {
@@ -153,6 +171,14 @@ impl<C> ChainNotificationHandler<C> {
 		T::Future: Send + 'static,
 	{
 		for &(ref subscriber, ref filter) in self.logs_subscribers.read().values() {
+			let (limit, direction) = (filter.limit, filter.limit_direction());
+			let filter = match filter.clone().try_into_eth_filter() {
+				Ok(filter) => filter,
+				Err(err) => {
+					warn!(target: "rpc", "Skipping logs subscription with invalid filter: {:?}", err);
+					continue;
+				},
+			};
 			let logs = futures::future::join_all(enacted
 				.iter()
 				.map(|hash| {
@@ -163,14 +189,13 @@ impl<C> ChainNotificationHandler<C> {
 				})
 				.collect::<Vec<_>>()
 			);
-			let limit = filter.limit;
 			let remote = self.remote.clone();
 			let subscriber = subscriber.clone();
 			self.remote.spawn(logs
 				.map(move |logs| {
 					let logs = logs.into_iter().flat_map(|log| log).collect();
 
-					for log in limit_logs(logs, limit) {
+					for log in limit_logs(logs, limit, direction) {
 						Self::notify(&remote, &subscriber, pubsub::Result::Log(log))
 					}
 				})
@@ -179,28 +204,176 @@ impl<C> ChainNotificationHandler<C> {
 		}
 	}
 
-	fn notify_return_data<F>(&self, enacted: &[H256], calculate_return_data: F) where
-		F: Fn(&[H256]) -> Vec<pubsub::ReturnData>
-	{
-		let return_datas = calculate_return_data(enacted);
+	/// Returns given hashes which haven't been notified yet and remembers them as notified.
+	fn unnotified_transactions(&self, hashes: &[H256]) -> Vec<H256> {
+		let mut notified = self.notified_transactions.lock();
+		let mut seen = HashSet::new();
+		let hashes = hashes.iter()
+			.filter(|hash| seen.insert(**hash) && !notified.contains(hash))
+			.cloned()
+			.collect::<Vec<_>>();
 
-		for subscriber in self.return_data_subscribers.read().values() {
-			let remote = self.remote.clone();
-			let subscriber = subscriber.clone();
-			for return_data in &return_datas {
-				let data = return_data.clone();
-				Self::notify(&remote, &subscriber, pubsub::Result::ReturnData(data))
+		notified.extend(hashes.iter().cloned());
+		notified.truncate_oldest(NOTIFIED_TRANSACTIONS_HISTORY);
+
+		hashes
+	}
+}
+
+impl<C: BlockChainClient + 'static> ChainNotificationHandler<C> {
+	/// Notify return data subscribers about transactions of given blocks,
+	/// with a batch of return data per block and subscriber.
+	/// Transactions are replayed on the pool, not on the import thread.
+	fn notify_return_data(&self, retracted: Vec<H256>, enacted: Vec<H256>) {
+		if (retracted.is_empty() && enacted.is_empty()) || self.return_data_subscribers.read().is_empty() {
+			return;
+		}
+
+		let client = self.client.clone();
+		let remote = self.remote.clone();
+		let subscribers = self.return_data_subscribers.clone();
+		// a single job, so that removals are always notified before the blocks replacing them
+		self.pool.spawn_fn(move || {
+			let local_transactions = client.local_transactions().into_iter().collect::<HashSet<_>>();
+			let subscribers = subscribers.read();
+			let blocks = retracted.into_iter().map(|block| (block, true))
+				.chain(enacted.into_iter().map(|block| (block, false)));
+			for (block, removed) in blocks {
+				Self::notify_block_return_data(&*client, &remote, &subscribers, &local_transactions, block, removed);
+			}
+			Ok::<(), ()>(())
+		}).forget();
+	}
+
+	fn notify_block_return_data(
+		client: &C,
+		remote: &Remote,
+		subscribers: &Subscribers<(Client, pubsub::ReturnDataOptions)>,
+		local_transactions: &HashSet<H256>,
+		block: H256,
+		removed: bool,
+	) {
+		let body = match client.block_body(BlockId::Hash(block)) {
+			Some(body) => body,
+			None => return,
+		};
+		let transactions = body.transactions();
+
+		// only local transactions are notified, optionally restricted to calls to given addresses
+		let selected = subscribers.values()
+			.map(|&(ref subscriber, ref options)| {
+				let addresses: Option<Vec<Address>> = options.addresses.as_ref()
+					.map(|addresses| addresses.iter().cloned().map(Into::into).collect());
+				let indices = transactions.iter()
+					.enumerate()
+					.filter(|&(_, transaction)| local_transactions.contains(&transaction.hash()))
+					.filter(|&(_, transaction)| match (addresses.as_ref(), &transaction.action) {
+						(None, _) => true,
+						(Some(addresses), &Action::Call(ref to)) => addresses.contains(to),
+						(Some(_), &Action::Create) => false,
+					})
+					.map(|(index, _)| index)
+					.take(options.max_per_block.unwrap_or(DEFAULT_RETURN_DATA_PER_BLOCK))
+					.collect::<Vec<_>>();
+				(subscriber, options, indices)
+			})
+			.collect::<Vec<_>>();
+
+		// the block is replayed once, whatever the number of subscribers
+		let indices = selected.iter()
+			.flat_map(|&(_, _, ref indices)| indices.iter().cloned())
+			.collect::<BTreeSet<_>>();
+		if indices.is_empty() {
+			return;
+		}
+		let replayed = indices.iter().map(|index| (*index, transactions[*index].hash())).collect();
+		let replayed = match replay_block_return_data(client, BlockId::Hash(block), replayed, removed) {
+			Ok(replayed) => indices.into_iter().zip(replayed).collect::<HashMap<_, _>>(),
+			Err(e) => {
+				warn!("Unable to calculate return data of transactions in block {}: {:?}", block, e);
+				return;
+			},
+		};
+
+		for (subscriber, options, indices) in selected {
+			let batch = indices.into_iter()
+				.map(|index| replayed[&index].clone())
+				.map(|return_data| match options.compress {
+					true => compress_return_data(return_data),
+					false => encode_return_data(return_data, options.encoding),
+				})
+				.collect::<Vec<_>>();
+			let (batch, truncated) = limit_size(batch, options.max_size_per_block, LimitDirection::First);
+
+			if !batch.is_empty() || truncated {
+				Self::notify(remote, subscriber, pubsub::Result::ReturnData(pubsub::ReturnDataBatch {
+					transactions: batch,
+					truncated: truncated,
+				}));
 			}
 		}
 	}
+}
 
+impl<C: PendingTransactions + 'static> ChainNotificationHandler<C> {
 	/// Notify all subscribers about new transaction hashes.
+	/// Transactions which were already notified are skipped.
 	pub fn new_transactions(&self, hashes: &[H256]) {
-		for subscriber in self.transactions_subscribers.read().values() {
-			for hash in hashes {
-				Self::notify(&self.remote, subscriber, pubsub::Result::TransactionHash((*hash).into()));
+		let hashes = self.unnotified_transactions(hashes);
+		if hashes.is_empty() {
+			return;
+		}
+
+		let subscribers = self.transactions_subscribers.read();
+		for &(ref subscriber, full) in subscribers.values() {
+			if !full {
+				for hash in &hashes {
+					Self::notify(&self.remote, subscriber, pubsub::Result::TransactionHash((*hash).into()));
+				}
 			}
 		}
+
+		// the pool may still be locked by the caller, transactions are fetched later
+		if subscribers.values().any(|&(_, full)| full) {
+			let client = self.client.clone();
+			let remote = self.remote.clone();
+			let subscribers = self.transactions_subscribers.clone();
+			self.remote.spawn_fn(move || {
+				let transactions = client.pending_transactions(&hashes);
+				for &(ref subscriber, full) in subscribers.read().values() {
+					if full {
+						for transaction in &transactions {
+							Self::notify(&remote, subscriber, pubsub::Result::FullTransaction(transaction.clone()));
+						}
+					}
+				}
+				Ok(())
+			});
+		}
+	}
+}
+
+/// Source of pending transactions for the full transactions subscribers.
+pub trait PendingTransactions: Send + Sync {
+	/// Get transactions with given hashes which are still in the pool,
+	/// whether they are ready for the next block or not. Transactions no longer in the pool are skipped.
+	fn pending_transactions(&self, hashes: &[H256]) -> Vec<Transaction>;
+}
+
+impl<C: BlockChainClient> PendingTransactions for C {
+	fn pending_transactions(&self, hashes: &[H256]) -> Vec<Transaction> {
+		let best_block = self.chain_info().best_block_number;
+		let eip86_transition = self.eip86_transition();
+		hashes.iter()
+			.filter_map(|hash| self.pending_transaction(hash))
+			.map(|transaction| Transaction::from_pending(transaction, best_block, eip86_transition))
+			.collect()
+	}
+}
+
+impl PendingTransactions for LightFetch {
+	fn pending_transactions(&self, _hashes: &[H256]) -> Vec<Transaction> {
+		Vec::new() // full transactions subscriptions are rejected for light clients.
 	}
 }
 
@@ -239,7 +412,7 @@ impl<C: LightClient> LightChainNotify for ChainNotificationHandler<C> {
 	}
 }
 
-impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
+impl<C: BlockChainClient + 'static> ChainNotify for ChainNotificationHandler<C> {
 	fn new_blocks(
 		&self,
 		_imported: Vec<H256>,
@@ -273,50 +446,13 @@ impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
 		self.notify_logs(&retracted, |filter| {
 			Ok(self.client.logs(filter).into_iter().map(Into::into).map(|mut log: Log| {
 				log.log_type = "removed".into();
+				log.removed = true;
 				log
 			}).collect())
 		});
 
-		fn replay_local_txns<C: BlockChainClient>(client: &C, local_transactions: &Vec<H256>, block_txn_hashes: Vec<H256>, removed: bool) -> Vec<pubsub::ReturnData> {
-			let analytics = CallAnalytics { transaction_tracing: false, vm_tracing: false, state_diffing: false, };
-			block_txn_hashes
-				.iter()
-				.filter(|txn_hash| local_transactions.contains(txn_hash))
-				.filter_map(|txn_hash| {
-					match client.replay(TransactionId::Hash(*txn_hash), analytics) {
-						Ok(executed) => {
-							Some(pubsub::ReturnData {
-								transaction_hash: *txn_hash,
-								return_data: executed.output.to_hex(),
-								removed: removed
-							})
-						},
-						Err(e) => {
-							warn!("Unable to calculate transaction return data for transaction hash {}: {:?}", *txn_hash, e);
-							None
-						}
-					}
-				})
-				.collect()
-		}
-
-		let local_transactions = self.client.local_transactions();
-		self.notify_return_data(&enacted,
-								|block_hashes: &[H256]| -> Vec<pubsub::ReturnData> {
-									block_hashes
-										.iter()
-										.filter_map(|hash| self.client.block_body(BlockId::Hash(*hash)) )
-										.flat_map(|body| replay_local_txns::<C>(&self.client, &local_transactions, body.transaction_hashes(), false) )
-										.collect()
-								});
-		self.notify_return_data(&retracted,
-								|block_hashes: &[H256]| -> Vec<pubsub::ReturnData> {
-									block_hashes
-										.iter()
-										.filter_map(|hash| self.client.block_body(BlockId::Hash(*hash)) )
-										.flat_map(|body| replay_local_txns::<C>(&self.client, &local_transactions, body.transaction_hashes(), true) )
-										.collect()
-								});
+		// Return data of transactions of retracted and enacted blocks
+		self.notify_return_data(retracted, enacted);
 	}
 }
 
@@ -339,26 +475,46 @@ impl<C: Send + Sync + 'static> EthPubSub for EthPubSubClient<C> {
 			(pubsub::Kind::NewHeads, _) => {
 				errors::invalid_params("newHeads", "Expected no parameters.")
 			},
-			(pubsub::Kind::Logs, Some(pubsub::Params::Logs(filter))) => {
-				self.logs_subscribers.write().push(subscriber, filter.into());
-				return;
+			(pubsub::Kind::Logs, Some(pubsub::Params::Logs(ref filter))) if filter.transaction_hash.is_some() => {
+				errors::invalid_params("transactionHash", "only supported by eth_getLogs")
+			},
+			(pubsub::Kind::Logs, Some(pubsub::Params::Logs(ref filter))) if filter.block_hashes.is_some() => {
+				errors::invalid_params("blockHashes", "only supported by eth_getLogs")
+			},
+			(pubsub::Kind::Logs, Some(pubsub::Params::Logs(filter))) => match filter.validate() {
+				Ok(()) => {
+					self.logs_subscribers.write().push(subscriber, filter);
+					return;
+				},
+				Err(err) => err,
 			},
 			(pubsub::Kind::Logs, _) => {
 				errors::invalid_params("logs", "Expected a filter object.")
 			},
 			(pubsub::Kind::NewPendingTransactions, None) => {
-				self.transactions_subscribers.write().push(subscriber);
+				self.transactions_subscribers.write().push(subscriber, false);
+				return;
+			},
+			(pubsub::Kind::NewPendingTransactions, Some(pubsub::Params::Transactions(true))) if !self.full_transactions => {
+				errors::light_unimplemented(Some("Full pending transactions are not available, subscribe to their hashes instead.".into()))
+			},
+			(pubsub::Kind::NewPendingTransactions, Some(pubsub::Params::Transactions(full))) => {
+				self.transactions_subscribers.write().push(subscriber, full);
 				return;
 			},
 			(pubsub::Kind::NewPendingTransactions, _) => {
-				errors::invalid_params("newPendingTransactions", "Expected no parameters.")
+				errors::invalid_params("newPendingTransactions", "Expected no parameters or a boolean.")
 			},
 			(pubsub::Kind::ReturnData, None) => {
-				self.return_data_subscribers.write().push(subscriber);
+				self.return_data_subscribers.write().push(subscriber, Default::default());
+				return;
+			},
+			(pubsub::Kind::ReturnData, Some(pubsub::Params::ReturnData(options))) => {
+				self.return_data_subscribers.write().push(subscriber, options);
 				return;
 			},
 			(pubsub::Kind::ReturnData, _) => {
-				errors::invalid_params("returnData", "Expected no parameters.")
+				errors::invalid_params("returnData", "Expected no parameters or return data options.")
 			},
 			_ => {
 				errors::unimplemented(None)
@@ -39,15 +39,15 @@ use parking_lot::{RwLock, Mutex};
 use rlp::UntrustedRlp;
 use transaction::SignedTransaction;
 
-use v1::impls::eth_filter::Filterable;
+use v1::impls::eth_filter::{Filterable, LogsTimeout, first_block_with_timestamp};
 use v1::helpers::{errors, limit_logs};
-use v1::helpers::{PollFilter, PollManager};
+use v1::helpers::{PollManager, SharedFilters};
 use v1::helpers::light_fetch::{self, LightFetch};
 use v1::traits::Eth;
 use v1::types::{
-	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
+	RichBlock, RichHeader, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
 	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
-	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
+	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U64 as RpcU64, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
 
@@ -61,23 +61,24 @@ pub struct EthClient<T> {
 	transaction_queue: Arc<RwLock<TransactionQueue>>,
 	accounts: Arc<AccountProvider>,
 	cache: Arc<Mutex<LightDataCache>>,
-	polls: Mutex<PollManager<PollFilter>>,
+	polls: SharedFilters,
 	gas_price_percentile: usize,
 }
 
 impl<T> EthClient<T> {
-	fn num_to_id(num: BlockNumber) -> BlockId {
+	fn num_to_id(num: BlockNumber) -> Result<BlockId> {
 		// Note: Here we treat `Pending` as `Latest`.
 		//       Since light clients don't produce pending blocks
 		//       (they don't have state) we can safely fallback to `Latest`.
 		match num {
-			BlockNumber::Num(n) => BlockId::Number(n),
-			BlockNumber::Earliest => BlockId::Earliest,
-			BlockNumber::Latest => BlockId::Latest,
+			BlockNumber::Num(n) => Ok(BlockId::Number(n)),
+			BlockNumber::Earliest => Ok(BlockId::Earliest),
+			BlockNumber::Latest => Ok(BlockId::Latest),
 			BlockNumber::Pending => {
 				warn!("`Pending` is deprecated and may be removed in future versions. Falling back to `Latest`");
-				BlockId::Latest
-			}
+				Ok(BlockId::Latest)
+			},
+			BlockNumber::Safe | BlockNumber::Finalized => Err(errors::unsupported_block_tag()),
 		}
 	}
 }
@@ -92,7 +93,7 @@ impl<T> Clone for EthClient<T> {
 			transaction_queue: self.transaction_queue.clone(),
 			accounts: self.accounts.clone(),
 			cache: self.cache.clone(),
-			polls: Mutex::new(PollManager::new()),
+			polls: Arc::new(Mutex::new(PollManager::new())),
 			gas_price_percentile: self.gas_price_percentile,
 		}
 	}
@@ -117,11 +118,16 @@ impl<T: LightChainClient + 'static> EthClient<T> {
 			transaction_queue,
 			accounts,
 			cache,
-			polls: Mutex::new(PollManager::new()),
+			polls: Arc::new(Mutex::new(PollManager::new())),
 			gas_price_percentile,
 		}
 	}
 
+	/// Returns filters installed through this client, e.g. to uninstall them with `parity_uninstallAllFilters`.
+	pub fn filters(&self) -> SharedFilters {
+		self.polls.clone()
+	}
+
 	/// Create a light data fetcher instance.
 	fn fetcher(&self) -> LightFetch {
 		LightFetch {
@@ -228,6 +234,10 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 		Ok(format!("{}", ::light::net::MAX_PROTOCOL_VERSION))
 	}
 
+	fn chain_id(&self) -> Result<Option<RpcU64>> {
+		Ok(self.client.signing_chain_id().map(RpcU64::from))
+	}
+
 	fn syncing(&self) -> Result<SyncStatus> {
 		if self.sync.is_major_importing() {
 			let chain_info = self.client.chain_info();
@@ -281,7 +291,7 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	}
 
 	fn balance(&self, address: RpcH160, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256> {
-		Box::new(self.fetcher().account(address.into(), Self::num_to_id(num.unwrap_or_default()))
+		Box::new(self.fetcher().account(address.into(), try_bf!(Self::num_to_id(num.unwrap_or_default())))
 			.map(|acc| acc.map_or(0.into(), |a| a.balance).into()))
 	}
 
@@ -294,11 +304,11 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	}
 
 	fn block_by_number(&self, num: BlockNumber, include_txs: bool) -> BoxFuture<Option<RichBlock>> {
-		Box::new(self.rich_block(Self::num_to_id(num), include_txs).map(Some))
+		Box::new(self.rich_block(try_bf!(Self::num_to_id(num)), include_txs).map(Some))
 	}
 
 	fn transaction_count(&self, address: RpcH160, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256> {
-		Box::new(self.fetcher().account(address.into(), Self::num_to_id(num.unwrap_or_default()))
+		Box::new(self.fetcher().account(address.into(), try_bf!(Self::num_to_id(num.unwrap_or_default())))
 			.map(|acc| acc.map_or(0.into(), |a| a.nonce).into()))
 	}
 
@@ -321,7 +331,7 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	fn block_transaction_count_by_number(&self, num: BlockNumber) -> BoxFuture<Option<RpcU256>> {
 		let (sync, on_demand) = (self.sync.clone(), self.on_demand.clone());
 
-		Box::new(self.fetcher().header(Self::num_to_id(num)).and_then(move |hdr| {
+		Box::new(self.fetcher().header(try_bf!(Self::num_to_id(num))).and_then(move |hdr| {
 			if hdr.transactions_root() == KECCAK_NULL_RLP {
 				Either::A(future::ok(Some(U256::from(0).into())))
 			} else {
@@ -353,7 +363,7 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	fn block_uncles_count_by_number(&self, num: BlockNumber) -> BoxFuture<Option<RpcU256>> {
 		let (sync, on_demand) = (self.sync.clone(), self.on_demand.clone());
 
-		Box::new(self.fetcher().header(Self::num_to_id(num)).and_then(move |hdr| {
+		Box::new(self.fetcher().header(try_bf!(Self::num_to_id(num))).and_then(move |hdr| {
 			if hdr.uncles_hash() == KECCAK_EMPTY_LIST_RLP {
 				Either::B(future::ok(Some(U256::from(0).into())))
 			} else {
@@ -367,7 +377,7 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	}
 
 	fn code_at(&self, address: RpcH160, num: Trailing<BlockNumber>) -> BoxFuture<Bytes> {
-		Box::new(self.fetcher().code(address.into(), Self::num_to_id(num.unwrap_or_default())).map(Into::into))
+		Box::new(self.fetcher().code(address.into(), try_bf!(Self::num_to_id(num.unwrap_or_default()))).map(Into::into))
 	}
 
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<RpcH256> {
@@ -439,7 +449,7 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 
 	fn transaction_by_block_number_and_index(&self, num: BlockNumber, idx: Index) -> BoxFuture<Option<Transaction>> {
 		let eip86 = self.client.eip86_transition();
-		Box::new(self.fetcher().block(Self::num_to_id(num)).map(move |block| {
+		Box::new(self.fetcher().block(try_bf!(Self::num_to_id(num))).map(move |block| {
 			light_fetch::extract_transaction_at_index(block, idx.value(), eip86)
 		}))
 	}
@@ -484,7 +494,7 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 
 	fn uncle_by_block_number_and_index(&self, num: BlockNumber, idx: Index) -> BoxFuture<Option<RichBlock>> {
 		let client = self.client.clone();
-		Box::new(self.fetcher().block(Self::num_to_id(num)).map(move |block| {
+		Box::new(self.fetcher().block(try_bf!(Self::num_to_id(num))).map(move |block| {
 			extract_uncle_at_index(block, idx, client)
 		}))
 	}
@@ -506,10 +516,22 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 	}
 
 	fn logs(&self, filter: Filter) -> BoxFuture<Vec<Log>> {
-		let limit = filter.limit;
+		if let Err(err) = filter.validate() {
+			return Box::new(future::err(err));
+		}
+
+		if filter.transaction_hash.is_some() {
+			return Box::new(future::err(errors::light_unimplemented(Some("Logs can't be filtered by transaction hash".into()))));
+		}
+
+		if filter.block_hashes.is_some() {
+			return Box::new(future::err(errors::light_unimplemented(Some("Logs can't be filtered by a set of block hashes".into()))));
+		}
 
-		Box::new(Filterable::logs(self, filter.into())
-			.map(move|logs| limit_logs(logs, limit)))
+		let (limit, direction) = (filter.limit, filter.limit_direction());
+
+		Box::new(Filterable::logs(self, try_bf!(filter.try_into_eth_filter()))
+			.map(move|logs| limit_logs(logs, limit, direction)))
 	}
 
 	fn work(&self, _timeout: Trailing<u64>) -> Result<Work> {
@@ -529,25 +551,87 @@ impl<T: LightChainClient + 'static> Eth for EthClient<T> {
 impl<T: LightChainClient + 'static> Filterable for EthClient<T> {
 	fn best_block_number(&self) -> u64 { self.client.chain_info().best_block_number }
 
+	fn best_block_hash(&self) -> ::ethereum_types::H256 { self.client.chain_info().best_block_hash }
+
 	fn block_hash(&self, id: BlockId) -> Option<RpcH256> {
 		self.client.block_hash(id).map(Into::into)
 	}
 
+	fn is_canonical(&self, block_hash: RpcH256) -> bool {
+		let hash: ::ethereum_types::H256 = block_hash.into();
+		self.client.block_header(BlockId::Hash(hash))
+			.and_then(|header| self.client.block_hash(BlockId::Number(header.number())))
+			.map_or(false, |canonical| canonical == hash)
+	}
+
+	fn block_header(&self, id: BlockId) -> Option<RichHeader> {
+		self.client.block_header(id).map(|header| RichHeader {
+			inner: header.into(),
+			extra_info: Default::default(),
+		})
+	}
+
+	fn first_block_since(&self, timestamp: u64) -> Option<u64> {
+		first_block_with_timestamp(self.best_block_number(), timestamp, |number| {
+			self.client.block_header(BlockId::Number(number)).map(|header| header.timestamp())
+		})
+	}
+
 	fn pending_transactions_hashes(&self, _block_number: u64) -> Vec<::ethereum_types::H256> {
 		Vec::new()
 	}
 
+	fn pending_transaction(&self, _hash: ::ethereum_types::H256) -> Option<Transaction> {
+		None
+	}
+
 	fn logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>> {
 		self.fetcher().logs(filter)
 	}
 
-	fn pending_logs(&self, _block_number: u64, _filter: &EthcoreFilter) -> Vec<Log> {
+	fn pending_logs(&self, _block_number: u64, _depth: usize, _filter: &EthcoreFilter) -> Vec<Log> {
 		Vec::new() // light clients don't mine.
 	}
 
-	fn polls(&self) -> &Mutex<PollManager<PollFilter>> {
+	fn has_pending_source(&self) -> bool {
+		false
+	}
+
+	fn removed_logs(&self, _block_hash: ::ethereum_types::H256, _filter: &EthcoreFilter) -> (Vec<Log>, u64) {
+		(Vec::new(), 0)
+	}
+
+	fn polls(&self) -> &SharedFilters {
 		&self.polls
 	}
+
+	fn logs_chunk_size(&self) -> Option<u64> {
+		None
+	}
+
+	fn max_block_range(&self) -> Option<u64> {
+		None
+	}
+
+	fn max_result_size(&self) -> Option<usize> {
+		None
+	}
+
+	fn max_future_blocks(&self) -> Option<u64> {
+		None
+	}
+
+	fn pending_logs_depth(&self) -> usize {
+		1
+	}
+
+	fn pending_transactions_history(&self) -> Option<usize> {
+		None
+	}
+
+	fn logs_timeout(&self) -> Option<&LogsTimeout> {
+		None
+	}
 }
 
 fn extract_uncle_at_index<T: LightChainClient>(block: encoded::Block, index: Index, client: Arc<T>) -> Option<RichBlock> {
@@ -19,6 +19,8 @@ use std::sync::Arc;
 use jsonrpc_core::Result;
 use ethsync::LightSyncProvider;
 use v1::traits::Net;
+use v1::helpers::errors;
+use v1::types::{NetSyncStatus, PeerInfo, PeerLatency};
 
 /// Net rpc implementation.
 pub struct NetClient<S: ?Sized> {
@@ -46,4 +48,16 @@ impl<S: ?Sized + Sync + Send + 'static> Net for NetClient<S> where S: LightSyncP
 	fn is_listening(&self) -> Result<bool> {
 		Ok(true)
 	}
+
+	fn peer_info(&self) -> Result<Vec<PeerInfo>> {
+		Ok(self.sync.peers().into_iter().map(Into::into).collect())
+	}
+
+	fn peer_latency(&self) -> Result<PeerLatency> {
+		Ok(PeerLatency::from(&self.sync.peers()[..]))
+	}
+
+	fn sync_status(&self) -> Result<NetSyncStatus> {
+		Err(errors::light_unimplemented(None))
+	}
 }
@@ -32,11 +32,12 @@ use ethcore::ids::BlockId;
 use light::client::LightChainClient;
 
 use jsonrpc_core::{Result, BoxFuture};
-use jsonrpc_core::futures::Future;
+use jsonrpc_core::futures::{future, Future};
 use jsonrpc_macros::Trailing;
-use v1::helpers::{self, errors, ipfs, SigningQueue, SignerService, NetworkSettings};
+use v1::helpers::{self, errors, ipfs, SigningQueue, SignerService, NetworkSettings, SharedFilters};
 use v1::helpers::dispatch::LightDispatcher;
 use v1::helpers::light_fetch::LightFetch;
+use v1::impls::eth_filter;
 use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
@@ -46,6 +47,7 @@ use v1::types::{
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
 	AccountInfo, HwAccountInfo, Header, RichHeader,
+	Index, FilterInfo, FilterState, FilterStats,
 };
 use Host;
 
@@ -60,6 +62,7 @@ pub struct ParityClient {
 	signer: Option<Arc<SignerService>>,
 	dapps_address: Option<Host>,
 	ws_address: Option<Host>,
+	filters: SharedFilters,
 	eip86_transition: u64,
 	gas_price_percentile: usize,
 }
@@ -77,6 +80,7 @@ impl ParityClient {
 		dapps_address: Option<Host>,
 		ws_address: Option<Host>,
 		gas_price_percentile: usize,
+		filters: SharedFilters,
 	) -> Self {
 		ParityClient {
 			light_dispatch,
@@ -87,6 +91,7 @@ impl ParityClient {
 			signer,
 			dapps_address,
 			ws_address,
+			filters,
 			eip86_transition: client.eip86_transition(),
 			client,
 			gas_price_percentile,
@@ -413,6 +418,7 @@ impl Parity for ParityClient {
 			BlockNumber::Num(n) => BlockId::Number(n),
 			BlockNumber::Earliest => BlockId::Earliest,
 			BlockNumber::Latest | BlockNumber::Pending => BlockId::Latest,
+			BlockNumber::Safe | BlockNumber::Finalized => return Box::new(future::err(errors::unsupported_block_tag())),
 		};
 
 		Box::new(self.fetcher().header(id).map(from_encoded))
@@ -430,4 +436,20 @@ impl Parity for ParityClient {
 		Box::new(self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err)))
 	}
+
+	fn list_filters(&self) -> Result<Vec<FilterInfo>> {
+		Ok(eth_filter::list_filters(&self.filters))
+	}
+
+	fn filter_info(&self, index: Index) -> Result<FilterInfo> {
+		eth_filter::filter_info(&self.filters, index)
+	}
+
+	fn filter_stats(&self) -> Result<FilterStats> {
+		Ok(eth_filter::filter_stats(&self.filters))
+	}
+
+	fn export_filter(&self, index: Index) -> Result<FilterState> {
+		eth_filter::export_filter(&self.filters, index)
+	}
 }
@@ -28,7 +28,7 @@ use hash::keccak_buffer;
 use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_core::futures::Future;
 use v1::helpers::dapps::DappsService;
-use v1::helpers::errors;
+use v1::helpers::{errors, SharedFilters};
 use v1::traits::ParitySet;
 use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
 
@@ -38,16 +38,18 @@ pub struct ParitySetClient<F> {
 	dapps: Option<Arc<DappsService>>,
 	fetch: F,
 	pool: CpuPool,
+	filters: SharedFilters,
 }
 
 impl<F: Fetch> ParitySetClient<F> {
 	/// Creates new `ParitySetClient` with given `Fetch`.
-	pub fn new(net: Arc<ManageNetwork>, dapps: Option<Arc<DappsService>>, fetch: F, p: CpuPool) -> Self {
+	pub fn new(net: Arc<ManageNetwork>, dapps: Option<Arc<DappsService>>, fetch: F, p: CpuPool, filters: SharedFilters) -> Self {
 		ParitySetClient {
 			net: net,
 			dapps: dapps,
 			fetch: fetch,
 			pool: p,
+			filters: filters,
 		}
 	}
 }
@@ -159,4 +161,8 @@ impl<F: Fetch> ParitySet for ParitySetClient<F> {
 	fn remove_transaction(&self, _hash: H256) -> Result<Option<Transaction>> {
 		Err(errors::light_unimplemented(None))
 	}
+
+	fn uninstall_all_filters(&self) -> Result<U256> {
+		Ok(self.filters.lock().remove_all_polls().into())
+	}
 }
@@ -16,12 +16,14 @@
 
 //! Traces api implementation.
 
-use jsonrpc_core::Result;
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_core::futures::future;
 use jsonrpc_macros::Trailing;
 use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::errors;
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, ReplayedReceipt, TraceResults, TraceOptions, H160, H256};
+use v1::types::pubsub::ReturnData;
 
 /// Traces api implementation.
 // TODO: all calling APIs should be possible w. proved remote TX execution.
@@ -65,4 +67,12 @@ impl Traces for TracesClient {
 	fn replay_block_transactions(&self,  _block_number: BlockNumber, _flags: TraceOptions) ->  Result<Vec<TraceResults>> {
 		Err(errors::light_unimplemented(None))
 	}
+
+	fn replay_block_return_data(&self, _block_number: BlockNumber, _addresses: Trailing<Vec<H160>>) -> BoxFuture<Vec<ReturnData>> {
+		Box::new(future::err(errors::light_unimplemented(None)))
+	}
+
+	fn replay_block_receipts(&self, _block_number: BlockNumber) -> BoxFuture<Vec<ReplayedReceipt>> {
+		Box::new(future::err(errors::light_unimplemented(None)))
+	}
 }
@@ -36,7 +36,7 @@ mod web3;
 pub mod light;
 
 pub use self::eth::{EthClient, EthClientOptions};
-pub use self::eth_filter::EthFilterClient;
+pub use self::eth_filter::{EthFilterClient, EthFilterOptions, FilterChangeStream};
 pub use self::eth_pubsub::EthPubSubClient;
 pub use self::net::NetClient;
 pub use self::parity::ParityClient;
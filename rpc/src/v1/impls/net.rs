@@ -19,6 +19,7 @@ use std::sync::Arc;
 use jsonrpc_core::Result;
 use ethsync::SyncProvider;
 use v1::traits::Net;
+use v1::types::{NetSyncStatus, PeerInfo, PeerLatency};
 
 /// Net rpc implementation.
 pub struct NetClient<S: ?Sized> {
@@ -50,4 +51,15 @@ impl<S: ?Sized> Net for NetClient<S> where S: SyncProvider + 'static {
 		Ok(true)
 	}
 
+	fn peer_info(&self) -> Result<Vec<PeerInfo>> {
+		Ok(self.sync.peers().into_iter().map(Into::into).collect())
+	}
+
+	fn peer_latency(&self) -> Result<PeerLatency> {
+		Ok(PeerLatency::from(&self.sync.peers()[..]))
+	}
+
+	fn sync_status(&self) -> Result<NetSyncStatus> {
+		Ok(self.sync.status().into())
+	}
 }
@@ -39,8 +39,9 @@ use updater::{Service as UpdateService};
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::{future, Future};
 use jsonrpc_macros::Trailing;
-use v1::helpers::{self, errors, fake_sign, ipfs, SigningQueue, SignerService, NetworkSettings};
+use v1::helpers::{self, errors, fake_sign, ipfs, SigningQueue, SignerService, NetworkSettings, SharedFilters};
 use v1::helpers::accounts::unwrap_provider;
+use v1::impls::eth_filter;
 use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
@@ -50,6 +51,7 @@ use v1::types::{
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
 	AccountInfo, HwAccountInfo, RichHeader,
+	Index, FilterInfo, FilterState, FilterStats,
 	block_number_to_id
 };
 use Host;
@@ -68,6 +70,7 @@ pub struct ParityClient<C, M, U>  {
 	signer: Option<Arc<SignerService>>,
 	dapps_address: Option<Host>,
 	ws_address: Option<Host>,
+	filters: SharedFilters,
 	eip86_transition: u64,
 }
 
@@ -88,6 +91,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 		signer: Option<Arc<SignerService>>,
 		dapps_address: Option<Host>,
 		ws_address: Option<Host>,
+		filters: SharedFilters,
 	) -> Self {
 		let eip86_transition = client.eip86_transition();
 		ParityClient {
@@ -103,6 +107,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 			signer,
 			dapps_address,
 			ws_address,
+			filters,
 			eip86_transition,
 		}
 	}
@@ -283,8 +288,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 				warn!("BlockNumber::Pending is unsupported");
 				return Ok(None);
 			},
-
-			num => block_number_to_id(num)
+			num => block_number_to_id(num)?
 		};
 
 		Ok(self.client
@@ -298,8 +302,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 				warn!("BlockNumber::Pending is unsupported");
 				return Ok(None);
 			},
-
-			num => block_number_to_id(num)
+			num => block_number_to_id(num)?
 		};
 
 		Ok(self.client
@@ -431,6 +434,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 				BlockNumber::Earliest => BlockId::Earliest,
 				BlockNumber::Latest => BlockId::Latest,
 				BlockNumber::Pending => unreachable!(), // Already covered
+				BlockNumber::Safe | BlockNumber::Finalized => return Box::new(future::err(errors::unsupported_block_tag())),
 			};
 
 			let header = try_bf!(self.client.block_header(id.clone()).ok_or(errors::unknown_block()));
@@ -472,6 +476,7 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 				BlockNumber::Earliest => BlockId::Earliest,
 				BlockNumber::Latest => BlockId::Latest,
 				BlockNumber::Pending => unreachable!(), // Already covered
+				BlockNumber::Safe | BlockNumber::Finalized => return Err(errors::unsupported_block_tag()),
 			};
 
 			let state = self.client.state_at(id).ok_or(errors::state_pruned())?;
@@ -489,4 +494,20 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		Box::new(self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err)))
 	}
+
+	fn list_filters(&self) -> Result<Vec<FilterInfo>> {
+		Ok(eth_filter::list_filters(&self.filters))
+	}
+
+	fn filter_info(&self, index: Index) -> Result<FilterInfo> {
+		eth_filter::filter_info(&self.filters, index)
+	}
+
+	fn filter_stats(&self) -> Result<FilterStats> {
+		Ok(eth_filter::filter_stats(&self.filters))
+	}
+
+	fn export_filter(&self, index: Index) -> Result<FilterState> {
+		eth_filter::export_filter(&self.filters, index)
+	}
 }
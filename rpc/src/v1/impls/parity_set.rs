@@ -30,7 +30,7 @@ use updater::{Service as UpdateService};
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_core::futures::Future;
 use v1::helpers::dapps::DappsService;
-use v1::helpers::errors;
+use v1::helpers::{errors, SharedFilters};
 use v1::traits::ParitySet;
 use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
 
@@ -43,6 +43,7 @@ pub struct ParitySetClient<C, M, U, F = fetch::Client> {
 	dapps: Option<Arc<DappsService>>,
 	fetch: F,
 	pool: CpuPool,
+	filters: SharedFilters,
 	eip86_transition: u64,
 }
 
@@ -58,6 +59,7 @@ impl<C, M, U, F> ParitySetClient<C, M, U, F>
 		dapps: Option<Arc<DappsService>>,
 		fetch: F,
 		pool: CpuPool,
+		filters: SharedFilters,
 	) -> Self {
 		ParitySetClient {
 			client: client.clone(),
@@ -67,6 +69,7 @@ impl<C, M, U, F> ParitySetClient<C, M, U, F>
 			dapps: dapps,
 			fetch: fetch,
 			pool: pool,
+			filters: filters,
 			eip86_transition: client.eip86_transition(),
 		}
 	}
@@ -204,4 +207,8 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 
 		Ok(self.miner.remove_pending_transaction(&*self.client, &hash).map(|t| Transaction::from_pending(t, block_number, self.eip86_transition)))
 	}
+
+	fn uninstall_all_filters(&self) -> Result<U256> {
+		Ok(self.filters.lock().remove_all_polls().into())
+	}
 }
@@ -19,15 +19,19 @@
 use std::sync::Arc;
 
 use ethcore::client::{MiningBlockChainClient, CallAnalytics, TransactionId, TraceId, StateClient, StateInfo, Call, BlockId};
+use ethereum_types::Address;
+use futures_cpupool::CpuPool;
 use rlp::UntrustedRlp;
-use transaction::SignedTransaction;
+use transaction::{Action, SignedTransaction};
 
-use jsonrpc_core::Result;
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_core::futures::future;
 use jsonrpc_macros::Trailing;
 use v1::Metadata;
 use v1::traits::Traces;
-use v1::helpers::{errors, fake_sign};
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256, block_number_to_id};
+use v1::helpers::{errors, fake_sign, replay_block_return_data, replay_block_receipts};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, ReplayedReceipt, TraceResults, TraceOptions, H160, H256, block_number_to_id};
+use v1::types::pubsub::ReturnData;
 
 fn to_call_analytics(flags: TraceOptions) -> CallAnalytics {
 	CallAnalytics {
@@ -37,16 +41,30 @@ fn to_call_analytics(flags: TraceOptions) -> CallAnalytics {
 	}
 }
 
+/// Converts the number of a block whose transactions are replayed to its id.
+fn replayed_block_id(block_number: BlockNumber) -> Result<BlockId> {
+	match block_number {
+		BlockNumber::Num(num) => Ok(BlockId::Number(num)),
+		BlockNumber::Earliest => Ok(BlockId::Earliest),
+		BlockNumber::Latest => Ok(BlockId::Latest),
+
+		BlockNumber::Pending => Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+		BlockNumber::Safe | BlockNumber::Finalized => Err(errors::unsupported_block_tag()),
+	}
+}
+
 /// Traces api implementation.
 pub struct TracesClient<C> {
 	client: Arc<C>,
+	pool: CpuPool,
 }
 
 impl<C> TracesClient<C> {
-	/// Creates new Traces client.
-	pub fn new(client: &Arc<C>) -> Self {
+	/// Creates new Traces client. Transactions are replayed on the given pool.
+	pub fn new(client: &Arc<C>, pool: CpuPool) -> Self {
 		TracesClient {
 			client: client.clone(),
+			pool: pool,
 		}
 	}
 }
@@ -58,6 +76,7 @@ impl<C, S> Traces for TracesClient<C> where
 	type Metadata = Metadata;
 
 	fn filter(&self, filter: TraceFilter) -> Result<Option<Vec<LocalizedTrace>>> {
+		filter.validate()?;
 		Ok(self.client.filter_traces(filter.into())
 			.map(|traces| traces.into_iter().map(LocalizedTrace::from).collect()))
 	}
@@ -65,7 +84,7 @@ impl<C, S> Traces for TracesClient<C> where
 	fn block_traces(&self, block_number: BlockNumber) -> Result<Option<Vec<LocalizedTrace>>> {
 		let id = match block_number {
 			BlockNumber::Pending => return Ok(None),
-			num => block_number_to_id(num)
+			num => block_number_to_id(num)?
 		};
 
 		Ok(self.client.block_traces(id)
@@ -99,6 +118,7 @@ impl<C, S> Traces for TracesClient<C> where
 			BlockNumber::Latest => BlockId::Latest,
 
 			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+			BlockNumber::Safe | BlockNumber::Finalized => return Err(errors::unsupported_block_tag()),
 		};
 
 		let mut state = self.client.state_at(id).ok_or(errors::state_pruned())?;
@@ -126,6 +146,7 @@ impl<C, S> Traces for TracesClient<C> where
 			BlockNumber::Latest => BlockId::Latest,
 
 			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+			BlockNumber::Safe | BlockNumber::Finalized => return Err(errors::unsupported_block_tag()),
 		};
 
 		let mut state = self.client.state_at(id).ok_or(errors::state_pruned())?;
@@ -148,6 +169,7 @@ impl<C, S> Traces for TracesClient<C> where
 			BlockNumber::Latest => BlockId::Latest,
 
 			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
+			BlockNumber::Safe | BlockNumber::Finalized => return Err(errors::unsupported_block_tag()),
 		};
 
 		let mut state = self.client.state_at(id).ok_or(errors::state_pruned())?;
@@ -165,16 +187,42 @@ impl<C, S> Traces for TracesClient<C> where
 	}
 
 	fn replay_block_transactions(&self, block_number: BlockNumber, flags: TraceOptions) -> Result<Vec<TraceResults>> {
-		let id = match block_number {
-			BlockNumber::Num(num) => BlockId::Number(num),
-			BlockNumber::Earliest => BlockId::Earliest,
-			BlockNumber::Latest => BlockId::Latest,
-
-			BlockNumber::Pending => return Err(errors::invalid_params("`BlockNumber::Pending` is not supported", ())),
-		};
+		let id = replayed_block_id(block_number)?;
 
 		self.client.replay_block_transactions(id, to_call_analytics(flags))
 			.map(|results| results.into_iter().map(TraceResults::from).collect())
 			.map_err(errors::call)
 	}
+
+	fn replay_block_return_data(&self, block_number: BlockNumber, addresses: Trailing<Vec<H160>>) -> BoxFuture<Vec<ReturnData>> {
+		let id = try_bf!(replayed_block_id(block_number));
+
+		let addresses: Option<Vec<Address>> = addresses.into().map(|addresses: Vec<H160>| addresses.into_iter().map(Into::into).collect());
+		let is_recipient = |action: &Action| match (action, addresses.as_ref()) {
+			(_, None) => true,
+			(&Action::Call(ref to), Some(addresses)) => addresses.contains(to),
+			(&Action::Create, Some(_)) => false,
+		};
+
+		let body = try_bf!(self.client.block_body(id).ok_or_else(errors::unknown_block));
+		let transactions = body.transactions()
+			.into_iter()
+			.enumerate()
+			.filter(|&(_, ref transaction)| is_recipient(&transaction.action))
+			.map(|(index, transaction)| (index, transaction.hash()))
+			.collect::<Vec<_>>();
+
+		// replaying may take long, so it's done off the RPC thread
+		let client = self.client.clone();
+		Box::new(self.pool.spawn_fn(move || replay_block_return_data(&*client, id, transactions, false).map_err(errors::call)))
+	}
+
+	fn replay_block_receipts(&self, block_number: BlockNumber) -> BoxFuture<Vec<ReplayedReceipt>> {
+		let id = try_bf!(replayed_block_id(block_number));
+		let body = try_bf!(self.client.block_body(id).ok_or_else(errors::unknown_block));
+		let hashes = body.transaction_hashes();
+
+		let client = self.client.clone();
+		Box::new(self.pool.spawn_fn(move || replay_block_receipts(&*client, id, hashes).map_err(errors::call)))
+	}
 }
@@ -15,23 +15,48 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Web3 rpc implementation.
+use std::collections::BTreeMap;
 use hash::keccak;
 use jsonrpc_core::Result;
+use jsonrpc_macros::Trailing;
 use version::version;
 use v1::traits::Web3;
-use v1::types::{H256, Bytes};
+use v1::types::{H256, Bytes, ClientVersion, ClientCapabilities};
 
 /// Web3 rpc implementation.
-pub struct Web3Client;
+pub struct Web3Client {
+	modules: BTreeMap<String, String>,
+	features: Vec<String>,
+}
 
 impl Web3Client {
 	/// Creates new Web3Client.
-	pub fn new() -> Self { Web3Client }
+	pub fn new() -> Self {
+		Web3Client::with_capabilities(BTreeMap::new(), Vec::new())
+	}
+
+	/// Creates new Web3Client reporting given enabled modules and their versions,
+	/// together with the optional features they actually provide.
+	pub fn with_capabilities(modules: BTreeMap<String, String>, features: Vec<String>) -> Self {
+		Web3Client {
+			modules: modules,
+			features: features,
+		}
+	}
 }
 
 impl Web3 for Web3Client {
-	fn client_version(&self) -> Result<String> {
-		Ok(version().to_owned().replace("Parity/", "Parity//"))
+	fn client_version(&self, detailed: Trailing<bool>) -> Result<ClientVersion> {
+		let version = version().to_owned().replace("Parity/", "Parity//");
+		if !detailed.unwrap_or(false) {
+			return Ok(ClientVersion::Version(version));
+		}
+
+		Ok(ClientVersion::Detailed(ClientCapabilities {
+			version: version,
+			namespaces: self.modules.keys().cloned().collect(),
+			features: self.features.clone(),
+		}))
 	}
 
 	fn sha3(&self, data: Bytes) -> Result<H256> {
@@ -232,7 +232,11 @@ impl MinerService for TestMinerService {
 
 	/// Returns hashes of transactions currently in pending
 	fn pending_transactions_hashes(&self, _best_block: BlockNumber) -> Vec<H256> {
-		vec![]
+		self.pending_transactions.lock().keys().cloned().collect()
+	}
+
+	fn recent_pending_transactions_hashes(&self, best_block: BlockNumber) -> Vec<H256> {
+		self.pending_transactions_hashes(best_block)
 	}
 
 	/// Removes all transactions from the queue and restart mining operation.
@@ -33,6 +33,8 @@ pub struct Config {
 pub struct TestSyncProvider {
 	/// Sync status.
 	pub status: RwLock<SyncStatus>,
+	/// Connected peers.
+	pub peers: RwLock<Vec<PeerInfo>>,
 }
 
 impl TestSyncProvider {
@@ -55,6 +57,38 @@ impl TestSyncProvider {
 				snapshot_chunks_done: 0,
 				last_imported_old_block_number: None,
 			}),
+			peers: RwLock::new(vec![
+				PeerInfo {
+					id: Some("node1".to_owned()),
+					client_version: "Parity/1".to_owned(),
+					capabilities: vec!["eth/62".to_owned(), "eth/63".to_owned()],
+					remote_address: "127.0.0.1:7777".to_owned(),
+					local_address: "127.0.0.1:8888".to_owned(),
+					originated: true,
+					eth_info: Some(EthProtocolInfo {
+						version: 62,
+						difficulty: Some(40.into()),
+						head: 50.into(),
+					}),
+					pip_info: None,
+					ping_ms: None,
+				},
+				PeerInfo {
+					id: None,
+					client_version: "Parity/2".to_owned(),
+					capabilities: vec!["eth/63".to_owned(), "eth/64".to_owned()],
+					remote_address: "Handshake".to_owned(),
+					local_address: "127.0.0.1:3333".to_owned(),
+					originated: false,
+					eth_info: Some(EthProtocolInfo {
+						version: 64,
+						difficulty: None,
+						head: 60.into()
+					}),
+					pip_info: None,
+					ping_ms: None,
+				}
+			]),
 		}
 	}
 
@@ -72,34 +106,7 @@ impl SyncProvider for TestSyncProvider {
 	}
 
 	fn peers(&self) -> Vec<PeerInfo> {
-		vec![
-			PeerInfo {
-				id: Some("node1".to_owned()),
-				client_version: "Parity/1".to_owned(),
-				capabilities: vec!["eth/62".to_owned(), "eth/63".to_owned()],
-				remote_address: "127.0.0.1:7777".to_owned(),
-				local_address: "127.0.0.1:8888".to_owned(),
-				eth_info: Some(EthProtocolInfo {
-					version: 62,
-					difficulty: Some(40.into()),
-					head: 50.into(),
-				}),
-				pip_info: None,
-			},
-			PeerInfo {
-				id: None,
-				client_version: "Parity/2".to_owned(),
-				capabilities: vec!["eth/63".to_owned(), "eth/64".to_owned()],
-				remote_address: "Handshake".to_owned(),
-				local_address: "127.0.0.1:3333".to_owned(),
-				eth_info: Some(EthProtocolInfo {
-					version: 64,
-					difficulty: None,
-					head: 60.into()
-				}),
-				pip_info: None,
-			}
-		]
+		self.peers.read().clone()
 	}
 
 	fn enode(&self) -> Option<String> {
@@ -32,14 +32,16 @@ use miner::external::ExternalMiner;
 use rlp;
 use rustc_hex::{FromHex, ToHex};
 use transaction::{Transaction, Action};
+use transient_hashmap::Timer;
 
 use jsonrpc_core::IoHandler;
-use v1::{Eth, EthClient, EthClientOptions, EthFilter, EthFilterClient, EthSigning, SigningUnsafeClient};
-use v1::helpers::nonce;
+use v1::{Eth, EthClient, EthClientOptions, EthFilter, EthFilterClient, EthFilterOptions, EthSigning, Parity, SigningUnsafeClient};
+use v1::helpers::{nonce, PollTimer, SharedFilters};
 use v1::helpers::dispatch::FullDispatcher;
 use v1::tests::helpers::{TestSyncProvider, Config, TestMinerService, TestSnapshotService};
 use v1::metadata::Metadata;
 use v1::types::Origin;
+use super::parity::Dependencies as ParityDependencies;
 
 fn blockchain_client() -> Arc<TestBlockChainClient> {
 	let client = TestBlockChainClient::new();
@@ -71,6 +73,7 @@ struct EthTester {
 	pub accounts_provider: Arc<AccountProvider>,
 	pub miner: Arc<TestMinerService>,
 	pub snapshot: Arc<TestSnapshotService>,
+	pub filters: SharedFilters,
 	hashrates: Arc<Mutex<HashMap<H256, (Instant, U256)>>>,
 	pub io: IoHandler<Metadata>,
 }
@@ -93,7 +96,11 @@ impl EthTester {
 		let external_miner = Arc::new(ExternalMiner::new(hashrates.clone()));
 		let gas_price_percentile = options.gas_price_percentile;
 		let eth = EthClient::new(&client, &snapshot, &sync, &opt_ap, &miner, &external_miner, options).to_delegate();
-		let filter = EthFilterClient::new(client.clone(), miner.clone()).to_delegate();
+		let filter = EthFilterClient::new(client.clone(), miner.clone());
+		let filters = filter.filters();
+		// filters are inspected through the parity API
+		let parity = ParityDependencies { filters: filters.clone(), ..ParityDependencies::new() }.client(None).to_delegate();
+		let filter = filter.to_delegate();
 		let reservations = Arc::new(Mutex::new(nonce::Reservations::new()));
 
 		let dispatcher = FullDispatcher::new(client.clone(), miner.clone(), reservations, gas_price_percentile);
@@ -102,6 +109,7 @@ impl EthTester {
 		io.extend_with(eth);
 		io.extend_with(sign);
 		io.extend_with(filter);
+		io.extend_with(parity);
 
 		EthTester {
 			client: client,
@@ -109,6 +117,7 @@ impl EthTester {
 			accounts_provider: ap,
 			miner: miner,
 			snapshot: snapshot,
+			filters: filters,
 			io: io,
 			hashrates: hashrates,
 		}
@@ -128,6 +137,17 @@ fn rpc_eth_protocol_version() {
 	assert_eq!(EthTester::default().io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_chain_id() {
+	let tester = EthTester::default();
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_chainId", "params": [], "id": 1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(r#"{"jsonrpc":"2.0","result":null,"id":1}"#.to_owned()));
+
+	*tester.client.chain_id.write() = Some(61);
+	assert_eq!(tester.io.handle_request_sync(request), Some(r#"{"jsonrpc":"2.0","result":"0x3d","id":1}"#.to_owned()));
+}
+
 #[test]
 fn rpc_eth_syncing() {
 	use ethcore::snapshot::RestorationStatus;
@@ -225,14 +245,114 @@ fn rpc_eth_logs() {
 	let request1 = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{}], "id": 1}"#;
 	let request2 = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"limit":1}], "id": 1}"#;
 	let request3 = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"limit":0}], "id": 1}"#;
+	let request4 = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"limit":1,"limitDirection":"first"}], "id": 1}"#;
 
-	let response1 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"},{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x1","topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x1","type":"mined"}],"id":1}"#;
-	let response2 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x1","topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x1","type":"mined"}],"id":1}"#;
+	let response1 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"},{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x1","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x1","type":"mined"}],"id":1}"#;
+	let response2 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x1","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x1","type":"mined"}],"id":1}"#;
 	let response3 = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	let response4 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"id":1}"#;
 
 	assert_eq!(tester.io.handle_request_sync(request1), Some(response1.to_owned()));
 	assert_eq!(tester.io.handle_request_sync(request2), Some(response2.to_owned()));
 	assert_eq!(tester.io.handle_request_sync(request3), Some(response3.to_owned()));
+	assert_eq!(tester.io.handle_request_sync(request4), Some(response4.to_owned()));
+}
+
+#[test]
+fn rpc_eth_logs_of_transaction() {
+	use serde_json;
+
+	let log = |transaction_hash: H256, address: u64, log_index: usize| LocalizedLogEntry {
+		block_number: 1,
+		block_hash: H256::default(),
+		entry: LogEntry {
+			address: address.into(),
+			topics: vec![],
+			data: vec![log_index as u8],
+		},
+		transaction_index: 0,
+		transaction_log_index: log_index,
+		transaction_hash: transaction_hash,
+		log_index: log_index,
+	};
+	let hash = H256::from(5);
+	let tester = EthTester::default();
+	tester.client.set_logs(vec![log(H256::from(6), 1, 0)]);
+	tester.client.set_transaction_receipt(TransactionId::Hash(hash), LocalizedReceipt {
+		transaction_hash: hash,
+		transaction_index: 0,
+		block_hash: H256::default(),
+		block_number: 1,
+		cumulative_gas_used: U256::from(0x20),
+		gas_used: U256::from(0x10),
+		contract_address: None,
+		logs: vec![log(hash, 1, 0), log(hash, 2, 1), log(hash, 1, 2)],
+		log_bloom: 0.into(),
+		outcome: TransactionOutcome::StateRoot(0.into()),
+	});
+
+	let logs = |filter: &str| -> serde_json::Value {
+		let request = format!(r#"{{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{}], "id": 1}}"#, filter);
+		serde_json::from_str(&tester.io.handle_request_sync(&request).unwrap()).unwrap()
+	};
+	let data = |response: serde_json::Value| response["result"].as_array().unwrap().iter()
+		.map(|log| {
+			assert_eq!(log["transactionHash"], format!("0x{:x}", hash));
+			log["data"].as_str().unwrap().to_owned()
+		})
+		.collect::<Vec<_>>();
+
+	let filter = format!(r#"{{"transactionHash":"0x{:x}"}}"#, hash);
+	assert_eq!(data(logs(&filter)), vec!["0x00", "0x01", "0x02"]);
+	let filter = format!(r#"{{"transactionHash":"0x{:x}","address":"0x0000000000000000000000000000000000000001","limit":1}}"#, hash);
+	assert_eq!(data(logs(&filter)), vec!["0x02"]);
+	let filter = format!(r#"{{"transactionHash":"0x{:x}"}}"#, H256::from(7));
+	assert!(data(logs(&filter)).is_empty());
+
+	let filter = format!(r#"{{"transactionHash":"0x{:x}","fromBlock":"0x1"}}"#, hash);
+	assert_eq!(logs(&filter)["error"]["code"], -32602);
+}
+
+#[test]
+fn rpc_eth_logs_of_block_hashes() {
+	use serde_json;
+
+	let tester = EthTester::default();
+	tester.client.add_blocks(3, EachBlockWith::Nothing);
+	let hash = |number: usize| tester.client.numbers.read()[&number];
+	let log = |block_number: u64, block_hash: H256| LocalizedLogEntry {
+		block_number: block_number,
+		block_hash: block_hash,
+		entry: LogEntry {
+			address: Address::default(),
+			topics: vec![],
+			data: vec![block_number as u8],
+		},
+		transaction_index: 0,
+		transaction_log_index: 0,
+		transaction_hash: H256::default(),
+		log_index: 0,
+	};
+	tester.client.set_logs(vec![log(3, hash(3)), log(2, hash(2)), log(1, hash(1))]);
+
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{{"blockHashes":["0x{:x}","0x{:x}","0x{:x}"]}}], "id": 1}}"#,
+		hash(2), H256::from(0xbad), hash(1),
+	);
+	let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(&request).unwrap()).unwrap();
+	let logs = response["result"].as_array().unwrap().iter()
+		.map(|log| (log["blockHash"].as_str().unwrap().to_owned(), log["data"].as_str().unwrap().to_owned()))
+		.collect::<Vec<_>>();
+
+	// the unknown block is skipped
+	assert_eq!(logs, vec![
+		(format!("0x{:x}", hash(1)), "0x01".to_owned()),
+		(format!("0x{:x}", hash(2)), "0x02".to_owned()),
+	]);
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{{"blockHashes":["0x{:x}"],"fromBlock":"0x1"}}], "id": 1}}"#, hash(1));
+	let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(&request).unwrap()).unwrap();
+	assert_eq!(response["error"]["code"], -32602);
 }
 
 #[test]
@@ -276,13 +396,272 @@ fn rpc_logs_filter() {
 
 	let request_changes1 = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
 	let request_changes2 = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x1"], "id": 1}"#;
-	let response1 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"},{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x1","topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x1","type":"mined"}],"id":1}"#;
-	let response2 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x1","topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x1","type":"mined"}],"id":1}"#;
+	let response1 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"},{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x1","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x1","type":"mined"}],"id":1}"#;
+	let response2 = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x1","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x1","type":"mined"}],"id":1}"#;
 
 	assert_eq!(tester.io.handle_request_sync(request_changes1), Some(response1.to_owned()));
 	assert_eq!(tester.io.handle_request_sync(request_changes2), Some(response2.to_owned()));
 }
 
+#[test]
+fn rpc_drain_filter() {
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+	tester.add_blocks(1, EachBlockWith::Nothing);
+	tester.client.set_logs(vec![LocalizedLogEntry {
+		block_number: 1,
+		block_hash: H256::default(),
+		entry: LogEntry {
+			address: Address::default(),
+			topics: vec![],
+			data: vec![1,2,3],
+		},
+		transaction_index: 0,
+		transaction_log_index: 0,
+		transaction_hash: H256::default(),
+		log_index: 0,
+	}]);
+
+	let request_drain = r#"{"jsonrpc": "2.0", "method": "parity_drainFilter", "params": ["0x0"], "id": 1}"#;
+	let response_drain = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_drain), Some(response_drain.to_owned()));
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response_not_found = r#"{"jsonrpc":"2.0","error":{"code":-32080,"message":"Filter not found"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response_not_found.to_owned()));
+	assert_eq!(tester.io.handle_request_sync(request_drain), Some(response_not_found.to_owned()));
+}
+
+fn pending_logs_tester() -> EthTester {
+	use ethcore::receipt::Receipt;
+
+	let tester = EthTester::default();
+	tester.client.set_logs(vec![LocalizedLogEntry {
+		block_number: 1,
+		block_hash: H256::default(),
+		entry: LogEntry {
+			address: Address::default(),
+			topics: vec![],
+			data: vec![1,2,3],
+		},
+		transaction_index: 0,
+		transaction_log_index: 0,
+		transaction_hash: H256::default(),
+		log_index: 0,
+	}]);
+	tester.miner.pending_receipts.lock().insert(H256::from(1), Receipt::new(TransactionOutcome::Unknown, 0.into(), vec![LogEntry {
+		address: Address::default(),
+		topics: vec![],
+		data: vec![4,5,6],
+	}]));
+	tester
+}
+
+fn assert_filter_logs(tester: &EthTester, filter: &str, include_pending: bool) {
+	let mined = r#"{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}"#;
+	let pending = r#"{"address":"0x0000000000000000000000000000000000000000","blockHash":null,"blockNumber":null,"data":"0x040506","logIndex":null,"removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000001","transactionIndex":null,"transactionLogIndex":null,"type":"pending"}"#;
+	let logs = match include_pending {
+		true => format!("{},{}", mined, pending),
+		false => mined.to_owned(),
+	};
+
+	let request_filter = format!(r#"{{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}}"#, filter);
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request_filter), Some(response.to_owned()));
+
+	let request_filter_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let request_logs = format!(r#"{{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{}], "id": 1}}"#, filter);
+	let response = format!(r#"{{"jsonrpc":"2.0","result":[{}],"id":1}}"#, logs);
+
+	assert_eq!(tester.io.handle_request_sync(request_filter_logs), Some(response.clone()));
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.clone()));
+	assert_eq!(tester.io.handle_request_sync(&request_logs), Some(response));
+}
+
+#[test]
+fn rpc_logs_filter_pending_changes() {
+	use ethcore::receipt::Receipt;
+
+	let tester = EthTester::default();
+	let pending_log = |data: Vec<u8>| LogEntry {
+		address: Address::default(),
+		topics: vec![],
+		data,
+	};
+	let pending_response = |tx: u64, data: &str| format!(r#"{{"address":"0x0000000000000000000000000000000000000000","blockHash":null,"blockNumber":null,"data":"{}","logIndex":null,"removed":false,"topics":[],"transactionHash":"0x{:064x}","transactionIndex":null,"transactionLogIndex":null,"type":"pending"}}"#, data, tx);
+	tester.miner.pending_receipts.lock().insert(H256::from(1), Receipt::new(TransactionOutcome::Unknown, 0.into(), vec![pending_log(vec![1])]));
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"toBlock":"pending"}], "id": 1}"#;
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+	let response = format!(r#"{{"jsonrpc":"2.0","result":[{}],"id":1}}"#, pending_response(1, "0x01"));
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response));
+
+	// a new transaction with two identical logs enters the pending block
+	tester.miner.pending_receipts.lock().insert(H256::from(2), Receipt::new(TransactionOutcome::Unknown, 0.into(), vec![pending_log(vec![2]), pending_log(vec![2])]));
+	let response = format!(r#"{{"jsonrpc":"2.0","result":[{},{}],"id":1}}"#, pending_response(2, "0x02"), pending_response(2, "0x02"));
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response));
+
+	// nothing is reported twice
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_logs_filter_from_pending() {
+	assert_filter_logs(&pending_logs_tester(), r#"{"fromBlock":"pending"}"#, true);
+}
+
+#[test]
+fn rpc_logs_filter_to_pending() {
+	assert_filter_logs(&pending_logs_tester(), r#"{"toBlock":"pending"}"#, true);
+}
+
+#[test]
+fn rpc_logs_filter_from_and_to_pending() {
+	assert_filter_logs(&pending_logs_tester(), r#"{"fromBlock":"pending","toBlock":"pending"}"#, true);
+}
+
+#[test]
+fn rpc_logs_filter_without_pending() {
+	assert_filter_logs(&pending_logs_tester(), r#"{"fromBlock":"latest","toBlock":"latest"}"#, false);
+}
+
+#[test]
+fn rpc_logs_filter_exclude_pending() {
+	assert_filter_logs(&pending_logs_tester(), r#"{"toBlock":"pending","excludePending":true}"#, false);
+}
+
+#[test]
+fn rpc_logs_filter_explicitly_include_pending() {
+	assert_filter_logs(&pending_logs_tester(), r#"{"toBlock":"pending","excludePending":false}"#, true);
+}
+
+#[test]
+fn rpc_logs_does_not_install_filters() {
+	let tester = pending_logs_tester();
+	let filter = r#"{"toBlock":"pending"}"#;
+
+	let request_filter = format!(r#"{{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}}"#, filter);
+	let request_filter_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+	let request_uninstall = r#"{"jsonrpc": "2.0", "method": "eth_uninstallFilter", "params": ["0x0"], "id": 1}"#;
+	let request_logs = format!(r#"{{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{}], "id": 1}}"#, filter);
+	let request_list = r#"{"jsonrpc": "2.0", "method": "parity_listFilters", "id": 1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+	let filter_logs = tester.io.handle_request_sync(request_filter_logs);
+	assert_eq!(tester.io.handle_request_sync(request_uninstall), Some(r#"{"jsonrpc":"2.0","result":true,"id":1}"#.to_owned()));
+
+	assert_eq!(tester.io.handle_request_sync(&request_logs), filter_logs);
+	assert_eq!(tester.io.handle_request_sync(request_list), Some(r#"{"jsonrpc":"2.0","result":[],"id":1}"#.to_owned()));
+}
+
+#[test]
+fn rpc_logs_filter_block_hash() {
+	let tester = EthTester::default();
+	tester.client.set_logs(vec![LocalizedLogEntry {
+		block_number: 1,
+		block_hash: H256::default(),
+		entry: LogEntry {
+			address: Address::default(),
+			topics: vec![],
+			data: vec![1,2,3],
+		},
+		transaction_index: 0,
+		transaction_log_index: 0,
+		transaction_hash: H256::default(),
+		log_index: 0,
+	}]);
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_logs_filter_block_hash_with_range() {
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x1","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: blockHash","data":"\"blockHash is mutually exclusive with fromBlock and toBlock\""},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"toBlock":"latest","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000"}], "id": 1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_logs), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_logs_filter_topic_positions() {
+	let tester = EthTester::default();
+	let request_four = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"topics":[null,null,null,"0x0000000000000000000000000000000000000000000000000000000000000001"]}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_four), Some(response.to_owned()));
+
+	let request_five = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"topics":[null,null,null,null,"0x0000000000000000000000000000000000000000000000000000000000000001"]}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: topics","data":"\"too many topic positions\""},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_five), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_logs_filter_reorg() {
+	let tester = EthTester::default();
+	tester.client.add_blocks(3, EachBlockWith::Nothing);
+	tester.client.set_logs(vec![LocalizedLogEntry {
+		block_number: 3,
+		block_hash: H256::default(),
+		entry: LogEntry {
+			address: Address::default(),
+			topics: vec![],
+			data: vec![1,2,3],
+		},
+		transaction_index: 0,
+		transaction_log_index: 0,
+		transaction_hash: H256::default(),
+		log_index: 0,
+	}]);
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let mined = r#"{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x3","data":"0x010203","logIndex":"0x0","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}"#;
+	let removed = r#"{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x3","data":"0x010203","logIndex":"0x0","removed":true,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"removed"}"#;
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":[{}],"id":1}}"#, mined);
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response));
+
+	// replace the two most recent blocks with blocks unknown to the filter
+	tester.client.numbers.write().insert(2, H256::from(2));
+	tester.client.numbers.write().insert(3, H256::from(3));
+
+	// the test client returns the same logs for every block, so each orphaned block
+	// reports its logs as removed before the logs of the new canonical chain
+	let response = format!(r#"{{"jsonrpc":"2.0","result":[{},{},{}],"id":1}}"#, removed, removed, mined);
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response));
+
+	// no further reorg
+	let response = format!(r#"{{"jsonrpc":"2.0","result":[{}],"id":1}}"#, mined);
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response));
+}
+
 #[test]
 fn rpc_blocks_filter() {
 	let tester = EthTester::default();
@@ -306,6 +685,717 @@ fn rpc_blocks_filter() {
 	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_blocks_filter_limit() {
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "params": [2], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	tester.client.add_blocks(5, EachBlockWith::Nothing);
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response = |numbers: &[u64]| {
+		let hashes: Vec<_> = numbers.iter()
+			.map(|n| format!(r#""0x{:x}""#, tester.client.block_hash(BlockId::Number(*n)).unwrap()))
+			.collect();
+		format!(r#"{{"jsonrpc":"2.0","result":[{}],"id":1}}"#, hashes.join(","))
+	};
+
+	// blocks exceeding the limit are returned by the next polls
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response(&[1, 2])));
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response(&[3, 4])));
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response(&[5])));
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response(&[])));
+}
+
+#[test]
+fn rpc_blocks_filter_rejects_zero_limit() {
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "params": [0], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: limit","data":"\"must be greater than zero\""},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_import = r#"{"jsonrpc": "2.0", "method": "parity_importFilter", "params": [{"type":"block","nextBlock":"0x0","limit":0}], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_import), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_block_headers_filter() {
+	use serde_json;
+
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockHeaderFilter", "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+
+	tester.client.add_blocks(2, EachBlockWith::Nothing);
+
+	let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(request_changes).unwrap()).unwrap();
+	let headers = response["result"].as_array().unwrap();
+	assert_eq!(headers.len(), 2);
+	assert_eq!(headers[0]["number"], "0x1");
+	assert_eq!(headers[0]["hash"], format!("0x{:x}", tester.client.block_hash(BlockId::Number(1)).unwrap()).as_str());
+	assert_eq!(headers[1]["number"], "0x2");
+	assert_eq!(headers[1]["hash"], format!("0x{:x}", tester.client.block_hash(BlockId::Number(2)).unwrap()).as_str());
+
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_pending_transaction_filter_full() {
+	use transaction::SignedTransaction;
+
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "params": [true], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+
+	{
+		let tx = rlp::decode(&FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap());
+		let tx = SignedTransaction::new(tx).unwrap();
+		tester.miner.pending_transactions.lock().insert(tx.hash(), tx);
+	}
+
+	let response = r#"{"jsonrpc":"2.0","result":[{"blockHash":null,"blockNumber":null,"chainId":null,"condition":null,"creates":null,"from":"0x0f65fe9276bc9a24ae7083ae28e2660ef72df99e","gas":"0x5208","gasPrice":"0x1","hash":"0x41df922fd0d4766fcc02e161f8295ec28522f329ae487f14d811e4b64c8d6e31","input":"0x","nonce":"0x0","publicKey":"0x7ae46da747962c2ee46825839c1ef9298e3bd2e70ca2938495c3693a485ec3eaa8f196327881090ff64cf4fbb0a48485d4f83098e189ed3b7a87d5941b59f789","r":"0x48b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353","raw":"0xf85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","s":"0xefffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804","standardV":"0x0","to":"0x095e7baea6a6c7c4c2dfeb977efac326af552d87","transactionIndex":null,"v":"0x1b","value":"0xa"}],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_pending_transaction_filter_full_sender() {
+	use ethkey::KeyPair;
+	use serde_json;
+
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "params": [true], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+	let keypair = KeyPair::from_secret(Secret::from_slice(&[69u8; 32])).unwrap();
+	let tx = Transaction {
+		nonce: 0.into(),
+		gas_price: 1.into(),
+		gas: 21_000.into(),
+		action: Action::Call(5.into()),
+		value: 0.into(),
+		data: vec![],
+	}.sign(keypair.secret(), None);
+	tester.miner.pending_transactions.lock().insert(tx.hash(), tx);
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(request_changes).unwrap()).unwrap();
+	assert_eq!(response["result"][0]["from"], format!("0x{:x}", keypair.address()).as_str());
+}
+
+#[test]
+fn rpc_pending_transaction_filter_large_pool() {
+	use std::collections::HashSet;
+	use serde_json;
+	use transaction::SignedTransaction;
+
+	let tester = EthTester::default();
+	let tx: SignedTransaction = {
+		let tx = rlp::decode(&FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap());
+		SignedTransaction::new(tx).unwrap()
+	};
+	let insert_pending = |range: ::std::ops::Range<u64>| {
+		let mut pending = tester.miner.pending_transactions.lock();
+		for i in range {
+			pending.insert(H256::from(i), tx.clone());
+		}
+	};
+	let changes = || -> HashSet<String> {
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+		let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(request).unwrap()).unwrap();
+		response["result"].as_array().unwrap().iter().map(|hash| hash.as_str().unwrap().to_owned()).collect()
+	};
+	let hashes = |range: ::std::ops::Range<u64>| -> HashSet<String> {
+		range.map(|i| format!("0x{:x}", H256::from(i))).collect()
+	};
+
+	insert_pending(0..10_000);
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+	assert_eq!(changes(), HashSet::new());
+
+	for round in 1..11 {
+		let start = 10_000 * round;
+		insert_pending(start..start + 1_000);
+		assert_eq!(changes(), hashes(start..start + 1_000));
+		assert_eq!(changes(), HashSet::new());
+	}
+
+	// hashes which left the pool and came back are reported again
+	tester.miner.pending_transactions.lock().remove(&H256::from(0));
+	assert_eq!(changes(), HashSet::new());
+	insert_pending(0..1);
+	assert_eq!(changes(), hashes(0..1));
+}
+
+#[test]
+fn rpc_pending_transaction_filter_history() {
+	use transaction::SignedTransaction;
+
+	let miner = miner_service();
+	let options = EthFilterOptions::with(|options| options.pending_transactions_history = Some(2));
+	let mut io: IoHandler<Metadata> = IoHandler::default();
+	io.extend_with(EthFilterClient::new_with_options(blockchain_client(), miner.clone(), options).to_delegate());
+
+	let tx: SignedTransaction = {
+		let tx = rlp::decode(&FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap());
+		SignedTransaction::new(tx).unwrap()
+	};
+	let changes = || {
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+		io.handle_request_sync(request).unwrap()
+	};
+	let response = |hashes: &[u64]| {
+		let hashes: Vec<_> = hashes.iter().map(|i| format!(r#""0x{:x}""#, H256::from(*i))).collect();
+		format!(r#"{{"jsonrpc":"2.0","result":[{}],"id":1}}"#, hashes.join(","))
+	};
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+	miner.pending_transactions.lock().insert(H256::from(1), tx.clone());
+	assert_eq!(changes(), response(&[1]));
+
+	// evicted and re-added transaction is not reported again
+	miner.pending_transactions.lock().remove(&H256::from(1));
+	assert_eq!(changes(), response(&[]));
+	miner.pending_transactions.lock().insert(H256::from(1), tx.clone());
+	assert_eq!(changes(), response(&[]));
+
+	// unless it was pushed out of the history by newer evictions
+	for i in 2..4 {
+		miner.pending_transactions.lock().insert(H256::from(i), tx.clone());
+		assert_eq!(changes(), response(&[i]));
+	}
+	for i in 1..4 {
+		miner.pending_transactions.lock().remove(&H256::from(i));
+		assert_eq!(changes(), response(&[]));
+	}
+	miner.pending_transactions.lock().insert(H256::from(2), tx.clone());
+	miner.pending_transactions.lock().insert(H256::from(3), tx.clone());
+	assert_eq!(changes(), response(&[]));
+	miner.pending_transactions.lock().insert(H256::from(1), tx.clone());
+	assert_eq!(changes(), response(&[1]));
+}
+
+#[test]
+fn rpc_pending_transaction_filter_limit() {
+	use std::collections::HashSet;
+	use serde_json;
+	use transaction::SignedTransaction;
+
+	let tester = EthTester::default();
+	let tx: SignedTransaction = {
+		let tx = rlp::decode(&FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap());
+		SignedTransaction::new(tx).unwrap()
+	};
+	let changes = || {
+		let request = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+		let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(request).unwrap()).unwrap();
+		response["result"].as_array().unwrap().iter()
+			.map(|hash| hash.as_str().unwrap().to_owned())
+			.collect::<Vec<_>>()
+	};
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "params": [{"limit": 2}], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+	for i in 1..4 {
+		tester.miner.pending_transactions.lock().insert(H256::from(i), tx.clone());
+	}
+
+	// transactions exceeding the limit are returned by the next poll
+	let first = changes();
+	let second = changes();
+	assert_eq!(first.len(), 2);
+	assert_eq!(second.len(), 1);
+	assert_eq!(changes(), Vec::<String>::new());
+
+	let reported: HashSet<_> = first.into_iter().chain(second).collect();
+	let expected: HashSet<_> = (1..4).map(|i| format!("0x{:x}", H256::from(i))).collect();
+	assert_eq!(reported, expected);
+}
+
+#[test]
+fn rpc_filter_logs() {
+	let tester = EthTester::default();
+	tester.client.set_logs(vec![LocalizedLogEntry {
+		block_number: 1,
+		block_hash: H256::default(),
+		entry: LogEntry {
+			address: Address::default(),
+			topics: vec![],
+			data: vec![1,2,3],
+		},
+		transaction_index: 0,
+		transaction_log_index: 0,
+		transaction_hash: H256::default(),
+		log_index: 0,
+	}]);
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"address":"0x0000000000000000000000000000000000000000","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000","blockNumber":"0x1","data":"0x010203","logIndex":"0x0","removed":false,"topics":[],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_logs), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_filter_logs_with_range() {
+	let tester = EthTester::default();
+	tester.client.add_blocks(5, EachBlockWith::Nothing);
+
+	let requests = [
+		r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x1"}], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"earliest","toBlock":"pending"}], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x2","toBlock":"0x64"}], "id": 1}"#,
+	];
+	for (id, request) in requests.iter().enumerate() {
+		let response = format!(r#"{{"jsonrpc":"2.0","result":"0x{}","id":1}}"#, id);
+		assert_eq!(tester.io.handle_request_sync(request), Some(response));
+	}
+
+	let request_logs = |id: usize| {
+		let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_getFilterLogsWithRange", "params": ["0x{}"], "id": 1}}"#, id);
+		tester.io.handle_request_sync(&request).unwrap()
+	};
+	let response = |from: u64, to: u64| format!(r#"{{"jsonrpc":"2.0","result":{{"fromBlock":"0x{:x}","logs":[],"toBlock":"0x{:x}","totalCount":"0x0"}},"id":1}}"#, from, to);
+
+	assert_eq!(request_logs(0), response(1, 5));
+	assert_eq!(request_logs(1), response(0, 5));
+	// blocks above the best block are not scanned
+	assert_eq!(request_logs(2), response(2, 5));
+
+	// `latest` is resolved at query time
+	tester.client.add_blocks(2, EachBlockWith::Nothing);
+	assert_eq!(request_logs(0), response(1, 7));
+}
+
+#[test]
+fn rpc_filter_logs_block_filter() {
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32081,"message":"Filter is not a log filter"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_logs), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_filter_logs_pending_transaction_filter() {
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32081,"message":"Filter is not a log filter"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_logs), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_filter_logs_unknown_filter() {
+	let tester = EthTester::default();
+	let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32080,"message":"Filter not found"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_logs), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_filter_changes_unknown_filter() {
+	let tester = EthTester::default();
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_uninstall = r#"{"jsonrpc": "2.0", "method": "eth_uninstallFilter", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_uninstall), Some(response.to_owned()));
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32080,"message":"Filter not found"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+	assert_eq!(tester.io.handle_request_sync(request_uninstall), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_filter_changes_filter_idle_for_too_long() {
+	struct TestTimer(Arc<Mutex<i64>>);
+
+	impl Timer for TestTimer {
+		fn get_time(&self) -> i64 {
+			*self.0.lock()
+		}
+	}
+
+	let tester = EthTester::default();
+	let time = Arc::new(Mutex::new(0));
+	tester.filters.lock().set_timer(PollTimer::new(TestTimer(time.clone())));
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(response.to_owned()));
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+
+	*time.lock() = 300;
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32080,"message":"Filter not found"},"id":1}"#;
+
+	*time.lock() = 601;
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_list_filters() {
+	use serde_json;
+
+	let tester = EthTester::default();
+	let requests = [
+		r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newBlockHeaderFilter", "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "id": 1}"#,
+	];
+	for (id, request) in requests.iter().enumerate() {
+		let response = format!(r#"{{"jsonrpc":"2.0","result":"0x{}","id":1}}"#, id);
+		assert_eq!(tester.io.handle_request_sync(request), Some(response));
+	}
+
+	let request_list = r#"{"jsonrpc": "2.0", "method": "parity_listFilters", "id": 1}"#;
+	let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(request_list).unwrap()).unwrap();
+	let filters = response["result"].as_array().unwrap().iter()
+		.map(|filter| (filter["id"].as_str().unwrap().to_owned(), filter["type"].as_str().unwrap().to_owned()))
+		.collect::<Vec<_>>();
+
+	assert_eq!(filters, vec![
+		("0x0".to_owned(), "logs".to_owned()),
+		("0x1".to_owned(), "block".to_owned()),
+		("0x2".to_owned(), "blockHeaders".to_owned()),
+		("0x3".to_owned(), "pendingTransaction".to_owned()),
+	]);
+	assert!(response["result"][0]["idleSeconds"].is_u64());
+}
+
+#[test]
+fn rpc_filter_stats() {
+	let tester = EthTester::default();
+	let requests = [
+		r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x1"}], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#,
+	];
+	for request in &requests {
+		tester.io.handle_request_sync(request).unwrap();
+	}
+
+	let request_stats = r#"{"jsonrpc": "2.0", "method": "parity_filterStats", "id": 1}"#;
+	let response_stats = r#"{"jsonrpc":"2.0","result":{"byType":{"block":"0x1","logs":"0x2"},"total":"0x3"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_stats), Some(response_stats.to_owned()));
+
+	// asking for stats doesn't change anything
+	assert_eq!(tester.io.handle_request_sync(request_stats), Some(response_stats.to_owned()));
+}
+
+#[test]
+fn rpc_filter_changes_batch() {
+	use serde_json;
+
+	let tester = EthTester::default();
+	let request_block_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_block_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#.to_owned()));
+
+	let poll_batch = || -> serde_json::Value {
+		let request = r#"{"jsonrpc": "2.0", "method": "parity_getFilterChangesBatch", "params": [["0x0", "0x1", "0x5"]], "id": 1}"#;
+		serde_json::from_str(&tester.io.handle_request_sync(request).unwrap()).unwrap()
+	};
+
+	let changes = poll_batch();
+	assert_eq!(changes["result"]["0x0"]["result"].as_array().map(Vec::len), Some(0));
+	assert_eq!(changes["result"]["0x1"]["result"].as_array().map(Vec::len), Some(0));
+	assert_eq!(changes["result"]["0x5"]["error"]["code"], -32080);
+	assert_eq!(changes["result"]["0x5"]["error"]["message"], "Filter not found");
+
+	// polled filters advance like with `eth_getFilterChanges`
+	tester.add_blocks(1, EachBlockWith::Nothing);
+	let changes = poll_batch();
+	assert_eq!(changes["result"]["0x0"]["result"].as_array().map(Vec::len), Some(1));
+	assert_eq!(poll_batch()["result"]["0x0"]["result"].as_array().map(Vec::len), Some(0));
+}
+
+#[test]
+fn rpc_filter_changes_by_type() {
+	use serde_json;
+
+	let tester = EthTester::default();
+	let request_block_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_block_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#.to_owned()));
+	assert_eq!(tester.io.handle_request_sync(request_block_filter), Some(r#"{"jsonrpc":"2.0","result":"0x2","id":1}"#.to_owned()));
+
+	tester.add_blocks(1, EachBlockWith::Nothing);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getFilterChangesByType", "params": ["block", ["0x0", "0x1", "0x5"]], "id": 1}"#;
+	let changes: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(request).unwrap()).unwrap();
+	let ids = changes["result"].as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+	assert_eq!(ids, vec!["0x0".to_owned(), "0x5".to_owned()]);
+	assert_eq!(changes["result"]["0x0"]["result"].as_array().map(Vec::len), Some(1));
+	assert_eq!(changes["result"]["0x5"]["error"]["code"], -32080);
+
+	// only the given block filter was advanced
+	let request_changes = |id: &str| format!(r#"{{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["{}"], "id": 1}}"#, id);
+	let response = Some(r#"{"jsonrpc":"2.0","result":[],"id":1}"#.to_owned());
+	assert_eq!(tester.io.handle_request_sync(&request_changes("0x0")), response);
+	let changes: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(&request_changes("0x2")).unwrap()).unwrap();
+	assert_eq!(changes["result"].as_array().map(Vec::len), Some(1));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getFilterChangesByType", "params": ["unknown", []], "id": 1}"#;
+	let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(request).unwrap()).unwrap();
+	assert_eq!(response["error"]["code"], -32602);
+}
+
+#[test]
+fn rpc_filter_changes_by_type_skips_filters_without_touching_them() {
+	struct TestTimer(Arc<Mutex<i64>>);
+
+	impl Timer for TestTimer {
+		fn get_time(&self) -> i64 {
+			*self.0.lock()
+		}
+	}
+
+	let tester = EthTester::default();
+	let time = Arc::new(Mutex::new(0));
+	tester.filters.lock().set_timer(PollTimer::new(TestTimer(time.clone())));
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+	*time.lock() = 200;
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getFilterChangesByType", "params": ["block", ["0x0"]], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(r#"{"jsonrpc":"2.0","result":{},"id":1}"#.to_owned()));
+
+	// the skipped filter still expires 300 seconds after it was created
+	*time.lock() = 301;
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32080,"message":"Filter not found"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_changes), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_filter_info() {
+	use serde_json;
+
+	let tester = EthTester::default();
+	tester.add_blocks(3, EachBlockWith::Nothing);
+
+	let filter = r#"{"fromBlock":"0x1","toBlock":"latest","address":"0x0000000000000000000000000000000000000005","topics":[null,["0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b"]],"limit":null}"#;
+	let request_filter = format!(r#"{{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}}"#, filter);
+	assert_eq!(tester.io.handle_request_sync(&request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+	let request_block_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_block_filter), Some(r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#.to_owned()));
+
+	let filter_info = |id: &str| -> serde_json::Value {
+		let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_getFilterInfo", "params": ["{}"], "id": 1}}"#, id);
+		serde_json::from_str(&tester.io.handle_request_sync(&request).unwrap()).unwrap()
+	};
+
+	let info = filter_info("0x0");
+	assert_eq!(info["result"]["type"], "logs");
+	assert_eq!(info["result"]["createdAt"], "0x3");
+	assert_eq!(info["result"]["filter"], serde_json::from_str::<serde_json::Value>(filter).unwrap());
+
+	let info = filter_info("0x1");
+	assert_eq!(info["result"]["type"], "block");
+	assert_eq!(info["result"]["createdAt"], "0x3");
+	assert!(info["result"].get("filter").is_none());
+
+	// checking the filter doesn't consume its changes
+	tester.add_blocks(1, EachBlockWith::Nothing);
+	filter_info("0x1");
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x1"], "id": 1}"#;
+	let response: serde_json::Value = serde_json::from_str(&tester.io.handle_request_sync(request_changes).unwrap()).unwrap();
+	assert_eq!(response["result"].as_array().unwrap().len(), 1);
+
+	let info = filter_info("0x2");
+	assert_eq!(info["error"]["code"], -32080);
+	assert_eq!(info["error"]["message"], "Filter not found");
+}
+
+#[test]
+fn rpc_pending_transaction_count() {
+	use transaction::SignedTransaction;
+
+	let tester = EthTester::default();
+	let tx: SignedTransaction = {
+		let tx = rlp::decode(&FromHex::from_hex("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap());
+		SignedTransaction::new(tx).unwrap()
+	};
+	for i in 0..5 {
+		tester.miner.pending_transactions.lock().insert(H256::from(i), tx.clone());
+	}
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_pendingTransactionCount", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x5","id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	// no filter has been installed
+	let request_list = r#"{"jsonrpc": "2.0", "method": "parity_listFilters", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request_list), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_filter_limit() {
+	let client = blockchain_client();
+	let miner = miner_service();
+	let options = EthFilterOptions::with(|options| options.poll_limit = 2);
+	let mut io: IoHandler<Metadata> = IoHandler::default();
+	io.extend_with(EthFilterClient::new_with_options(client, miner, options).to_delegate());
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+	let request_log_filter = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#;
+	let response_limit = r#"{"jsonrpc":"2.0","error":{"code":-32082,"message":"Filter has been rejected because of filters limit."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+	assert_eq!(io.handle_request_sync(request_log_filter), Some(r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#.to_owned()));
+	assert_eq!(io.handle_request_sync(request_filter), Some(response_limit.to_owned()));
+	assert_eq!(io.handle_request_sync(request_log_filter), Some(response_limit.to_owned()));
+
+	let request_uninstall = r#"{"jsonrpc": "2.0", "method": "eth_uninstallFilter", "params": ["0x0"], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request_uninstall), Some(r#"{"jsonrpc":"2.0","result":true,"id":1}"#.to_owned()));
+
+	assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x2","id":1}"#.to_owned()));
+}
+
+#[test]
+fn rpc_filter_max_block_range() {
+	let client = blockchain_client();
+	client.add_blocks(15, EachBlockWith::Nothing);
+	let miner = miner_service();
+	let options = EthFilterOptions::with(|options| options.max_block_range = Some(10));
+	let mut io: IoHandler<Metadata> = IoHandler::default();
+	io.extend_with(EthFilterClient::new_with_options(client.clone(), miner, options).to_delegate());
+
+	let request_in_range = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x1","toBlock":"0xb"}], "id": 1}"#;
+	let request_over_range = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x1","toBlock":"0xc"}], "id": 1}"#;
+	let request_to_latest = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0x5"}], "id": 1}"#;
+	let request_from_earliest = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"earliest"}], "id": 1}"#;
+	let response_too_large = r#"{"jsonrpc":"2.0","error":{"code":-32083,"message":"Filter block range is too large.","data":"Maximal range is 10 blocks"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request_in_range), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+	assert_eq!(io.handle_request_sync(request_over_range), Some(response_too_large.to_owned()));
+	assert_eq!(io.handle_request_sync(request_to_latest), Some(r#"{"jsonrpc":"2.0","result":"0x1","id":1}"#.to_owned()));
+	assert_eq!(io.handle_request_sync(request_from_earliest), Some(response_too_large.to_owned()));
+
+	// the range of an installed filter grows with the chain
+	client.add_blocks(1, EachBlockWith::Nothing);
+	let request_logs = r#"{"jsonrpc": "2.0", "method": "eth_getFilterLogs", "params": ["0x1"], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request_logs), Some(response_too_large.to_owned()));
+}
+
+#[test]
+fn rpc_filter_changes_polled_too_often() {
+	let miner = miner_service();
+	let options = EthFilterOptions::with(|options| options.min_poll_interval = Some(Duration::from_secs(60)));
+	let mut io: IoHandler<Metadata> = IoHandler::default();
+	io.extend_with(EthFilterClient::new_with_options(blockchain_client(), miner, options).to_delegate());
+
+	let request_filter = r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request_filter), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+	let request_changes = r#"{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x0"], "id": 1}"#;
+	assert_eq!(io.handle_request_sync(request_changes), Some(r#"{"jsonrpc":"2.0","result":[],"id":1}"#.to_owned()));
+	assert_eq!(
+		io.handle_request_sync(request_changes),
+		Some(r#"{"jsonrpc":"2.0","error":{"code":-32085,"message":"Filter changes have been requested too often."},"id":1}"#.to_owned())
+	);
+}
+
+#[test]
+fn rpc_block_tags_without_finality() {
+	let tester = EthTester::default();
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"`safe` and `finalized` blocks are not supported."},"id":1}"#;
+
+	let requests = [
+		r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"safe"}], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"toBlock":"finalized"}], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_getBlockByNumber", "params": ["finalized", false], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_getBalance", "params": ["0x0000000000000000000000000000000000000001", "safe"], "id": 1}"#,
+	];
+	for request in &requests {
+		assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+	}
+}
+
+#[test]
+fn rpc_filter_max_future_blocks() {
+	let client = blockchain_client();
+	client.add_blocks(10, EachBlockWith::Nothing);
+	let miner = miner_service();
+	let request_near_future = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0xf"}], "id": 1}"#;
+	let request_far_future = r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{"fromBlock":"0xffffffffffff"}], "id": 1}"#;
+
+	let mut io: IoHandler<Metadata> = IoHandler::default();
+	io.extend_with(EthFilterClient::new(client.clone(), miner.clone()).to_delegate());
+	assert_eq!(io.handle_request_sync(request_far_future), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+
+	let options = EthFilterOptions::with(|options| options.max_future_blocks = Some(5));
+	let mut io: IoHandler<Metadata> = IoHandler::default();
+	io.extend_with(EthFilterClient::new_with_options(client, miner, options).to_delegate());
+	let response_future = r#"{"jsonrpc":"2.0","error":{"code":-32084,"message":"Filter starts too far beyond the best block.","data":"Best block is 10, filters may start at most 5 blocks later"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request_near_future), Some(r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#.to_owned()));
+	assert_eq!(io.handle_request_sync(request_far_future), Some(response_future.to_owned()));
+}
+
 #[test]
 fn rpc_eth_submit_hashrate() {
 	let tester = EthTester::default();
@@ -1044,7 +2134,7 @@ fn rpc_eth_transaction_receipt() {
 		"params": ["0xb903239f8543d04b5dc1ba6579132b143087c68db1b2168786408fcbce568238"],
 		"id": 1
 	}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","contractAddress":null,"cumulativeGasUsed":"0x20","gasUsed":"0x10","logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","data":"0x","logIndex":"0x1","topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","root":"0x0000000000000000000000000000000000000000000000000000000000000000","status":null,"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0"},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","contractAddress":null,"cumulativeGasUsed":"0x20","gasUsed":"0x10","logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","data":"0x","logIndex":"0x1","removed":false,"topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","root":"0x0000000000000000000000000000000000000000000000000000000000000000","status":null,"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0"},"id":1}"#;
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
@@ -19,6 +19,7 @@ use std::sync::Arc;
 use jsonrpc_core::MetaIoHandler;
 use jsonrpc_core::futures::{self, Stream, Future};
 use jsonrpc_pubsub::Session;
+use serde_json;
 
 use v1::{EthPubSub, EthPubSubClient, Metadata};
 
@@ -78,6 +79,62 @@ fn should_subscribe_to_new_heads() {
 	assert_eq!(res, None);
 }
 
+#[test]
+fn should_notify_new_canonical_head_on_reorg() {
+	use ethereum_types::H256;
+	use serde_json;
+
+	// given
+	let el = EventLoop::spawn();
+	let mut client = TestBlockChainClient::new();
+	client.add_blocks(3, EachBlockWith::Nothing);
+	let h3 = client.block_hash_delta_minus(1);
+	let h2 = client.block_hash_delta_minus(2);
+	let h1 = client.block_hash_delta_minus(3);
+
+	let pubsub = EthPubSubClient::new_test(Arc::new(client), el.remote());
+	let handler = pubsub.handler().upgrade().unwrap();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["newHeads"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	let head = |notification: Option<String>| -> (String, String) {
+		let notification: serde_json::Value = serde_json::from_str(&notification.unwrap()).unwrap();
+		let header = &notification["params"]["result"];
+		(header["number"].as_str().unwrap().to_owned(), header["hash"].as_str().unwrap().to_owned())
+	};
+
+	// when the chain advances by two blocks
+	handler.new_blocks(vec![], vec![], vec![h1, h2], vec![], vec![], vec![], 0);
+
+	// then
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(head(res), ("0x1".to_owned(), format!("0x{:x}", h1)));
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(head(res), ("0x2".to_owned(), format!("0x{:x}", h2)));
+
+	// when a reorg retracts a block and enacts its replacement
+	handler.new_blocks(vec![], vec![], vec![h3], vec![H256::from(42)], vec![], vec![], 0);
+
+	// then only the new canonical head is pushed
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(head(res), ("0x3".to_owned(), format!("0x{:x}", h3)));
+
+	// disconnecting unregisters the subscription
+	drop(metadata);
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
+
 #[test]
 fn should_subscribe_to_logs() {
 	use ethcore::log_entry::{LocalizedLogEntry, LogEntry};
@@ -127,7 +184,7 @@ fn should_subscribe_to_logs() {
 	// Check notifications (enacted)
 	handler.new_blocks(vec![], vec![], vec![h1], vec![], vec![], vec![], 0);
 	let (res, receiver) = receiver.into_future().wait().unwrap();
-	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"address":"0x0000000000000000000000000000000000000005","blockHash":"0x3457d2fa2e3dd33c78ac681cf542e429becf718859053448748383af67e23218","blockNumber":"0x1","data":"0x","logIndex":"0x0","topics":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000000000000000000000000000000000000000000000000000000000000000"],"transactionHash":""#.to_owned()
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"address":"0x0000000000000000000000000000000000000005","blockHash":"0x3457d2fa2e3dd33c78ac681cf542e429becf718859053448748383af67e23218","blockNumber":"0x1","data":"0x","logIndex":"0x0","removed":false,"topics":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000000000000000000000000000000000000000000000000000000000000000"],"transactionHash":""#.to_owned()
 		+ &format!("0x{:?}", tx_hash)
 		+ r#"","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"},"subscription":"0x416d77337e24399d"}}"#;
 	assert_eq!(res, Some(response.into()));
@@ -135,7 +192,7 @@ fn should_subscribe_to_logs() {
 	// Check notifications (retracted)
 	handler.new_blocks(vec![], vec![], vec![], vec![h1], vec![], vec![], 0);
 	let (res, receiver) = receiver.into_future().wait().unwrap();
-	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"address":"0x0000000000000000000000000000000000000005","blockHash":"0x3457d2fa2e3dd33c78ac681cf542e429becf718859053448748383af67e23218","blockNumber":"0x1","data":"0x","logIndex":"0x0","topics":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000000000000000000000000000000000000000000000000000000000000000"],"transactionHash":""#.to_owned()
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":{"address":"0x0000000000000000000000000000000000000005","blockHash":"0x3457d2fa2e3dd33c78ac681cf542e429becf718859053448748383af67e23218","blockNumber":"0x1","data":"0x","logIndex":"0x0","removed":true,"topics":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000000000000000000000000000000000000000000000000000000000000000"],"transactionHash":""#.to_owned()
 		+ &format!("0x{:?}", tx_hash)
 		+ r#"","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"removed"},"subscription":"0x416d77337e24399d"}}"#;
 	assert_eq!(res, Some(response.into()));
@@ -150,6 +207,27 @@ fn should_subscribe_to_logs() {
 	assert_eq!(res, None);
 }
 
+#[test]
+fn should_reject_invalid_logs_filter() {
+	// given
+	let el = EventLoop::spawn();
+	let client = TestBlockChainClient::new();
+	let pubsub = EthPubSubClient::new_test(Arc::new(client), el.remote());
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, _receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	// Subscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["logs", {"topics":[null,null,null,null,null]}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: topics","data":"\"too many topic positions\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+}
+
 
 #[test]
 fn should_subscribe_to_pending_transactions() {
@@ -170,7 +248,7 @@ fn should_subscribe_to_pending_transactions() {
 
 	// Fail if params are provided
 	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["newPendingTransactions", {}], "id": 1}"#;
-	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: newPendingTransactions","data":"\"Expected no parameters.\""},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: newPendingTransactions","data":"\"Expected no parameters or a boolean.\""},"id":1}"#;
 	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
 
 	// Subscribe
@@ -198,6 +276,133 @@ fn should_subscribe_to_pending_transactions() {
 	assert_eq!(res, None);
 }
 
+#[test]
+fn should_notify_each_pending_transaction_once() {
+	// given
+	let el = EventLoop::spawn();
+	let client = TestBlockChainClient::new();
+
+	let pubsub = EthPubSubClient::new_test(Arc::new(client), el.remote());
+	let handler = pubsub.handler().upgrade().unwrap();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["newPendingTransactions"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// when
+	handler.new_transactions(&[5.into()]);
+	handler.new_transactions(&[5.into(), 7.into(), 7.into()]);
+
+	// then
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":"0x0000000000000000000000000000000000000000000000000000000000000005","subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":"0x0000000000000000000000000000000000000000000000000000000000000007","subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	// no more notifications after unsubscribing
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_unsubscribe", "params": ["0x416d77337e24399d"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata), Some(response.to_owned()));
+
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
+
+#[test]
+fn should_subscribe_to_full_pending_transactions() {
+	// given
+	let el = EventLoop::spawn();
+	let client = Arc::new(TestBlockChainClient::new());
+	let hash = client.insert_transaction_to_queue();
+
+	let pubsub = EthPubSubClient::new_test(client, el.remote());
+	let handler = pubsub.handler().upgrade().unwrap();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["newPendingTransactions", true], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// when
+	handler.new_transactions(&[hash]);
+
+	// then
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	let notification: serde_json::Value = serde_json::from_str(&res.unwrap()).unwrap();
+	assert_eq!(notification["params"]["result"]["hash"], format!("0x{:x}", hash));
+}
+
+#[test]
+fn should_notify_full_pending_transactions_not_ready_yet() {
+	use ethcore::miner::MinerService;
+	use ethereum_types::H256;
+	use ethkey::{Generator, Random};
+	use transaction::{Action, ImportResult, Transaction};
+
+	// given
+	let el = EventLoop::spawn();
+	let client = Arc::new(TestBlockChainClient::new());
+	let keypair = Random.generate().unwrap();
+	let transaction = Transaction {
+		action: Action::Create,
+		value: 100.into(),
+		data: vec![],
+		gas: 100_000.into(),
+		gas_price: 20_000_000_000u64.into(),
+		// the previous nonce is missing
+		nonce: 1.into(),
+	}.sign(keypair.secret(), None);
+	client.set_balance(transaction.sender(), 10_000_000_000_000_000_000u64.into());
+	let hash = transaction.hash();
+	let res = client.miner.import_external_transactions(&*client, vec![transaction.into()]);
+	assert_eq!(res.into_iter().next().unwrap().unwrap(), ImportResult::Future);
+
+	let pubsub = EthPubSubClient::new_test(client, el.remote());
+	let handler = pubsub.handler().upgrade().unwrap();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["newPendingTransactions", true], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// when a transaction which is not in the pool is notified along with it
+	handler.new_transactions(&[H256::from(5), hash]);
+
+	// then only the pooled transaction is pushed
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let notification: serde_json::Value = serde_json::from_str(&res.unwrap()).unwrap();
+	assert_eq!(notification["params"]["result"]["hash"], format!("0x{:x}", hash));
+
+	drop(metadata);
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
+
 #[test]
 fn should_return_unimplemented() {
 	// given
@@ -218,3 +423,108 @@ fn should_return_unimplemented() {
 	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["syncing"], "id": 1}"#;
 	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
 }
+
+#[test]
+fn should_notify_return_data_of_each_block() {
+	use ethcore::client::{BlockChainClient, ImportBlock};
+	use ethcore::executed::Executed;
+	use ethcore::header::Header;
+	use ethcore::miner::MinerService;
+	use ethereum_types::H256;
+	use ethkey::{Generator, Random};
+	use rlp::RlpStream;
+	use transaction::{Action, SignedTransaction, Transaction};
+
+	fn import_block_with_calls(client: &TestBlockChainClient, recipients: &[u64]) -> (H256, Vec<SignedTransaction>) {
+		let keypair = Random.generate().unwrap();
+		let transactions = recipients.iter().enumerate().map(|(nonce, recipient)| Transaction {
+			nonce: (nonce as u64).into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Call((*recipient).into()),
+			value: 0.into(),
+			data: vec![],
+		}.sign(keypair.secret(), None)).collect::<Vec<_>>();
+
+		let mut header = Header::new();
+		header.set_number(client.chain_info().best_block_number + 1);
+		header.set_parent_hash(*client.last_hash.read());
+		let mut block = RlpStream::new_list(3);
+		block.append(&header);
+		block.append_list::<SignedTransaction, _>(&transactions);
+		block.begin_list(0);
+		let hash = client.import_block(block.out()).unwrap();
+
+		(hash, transactions)
+	}
+
+	// given
+	let el = EventLoop::spawn();
+	let client = TestBlockChainClient::new();
+	client.set_execution_result(Ok(Executed {
+		exception: None,
+		gas: 20_000.into(),
+		gas_used: 10_000.into(),
+		refunded: 0.into(),
+		cumulative_gas_used: 10_000.into(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![1, 2, 3],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+	}));
+	let (h1, first) = import_block_with_calls(&client, &[5, 6, 5]);
+	let (h2, second) = import_block_with_calls(&client, &[5]);
+	let (h3, third) = import_block_with_calls(&client, &[5]);
+	// only local transactions are notified
+	for transaction in first[..2].iter().chain(&second).chain(&third) {
+		client.miner.import_own_transaction(&client, transaction.clone().into()).unwrap();
+	}
+
+	let pubsub = EthPubSubClient::new_test(Arc::new(client), el.remote());
+	let handler = pubsub.handler().upgrade().unwrap();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["returnData", {"addresses":["0x0000000000000000000000000000000000000005"]}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	let batch = |notification: Option<String>| -> Vec<(String, bool)> {
+		let notification: serde_json::Value = serde_json::from_str(&notification.unwrap()).unwrap();
+		notification["params"]["result"]["transactions"].as_array().unwrap().iter()
+			.map(|data| (data["transactionHash"].as_str().unwrap().to_owned(), data["removed"].as_bool().unwrap()))
+			.collect()
+	};
+	let hash = |transaction: &SignedTransaction| format!("0x{:x}", transaction.hash());
+
+	// when the chain advances by two blocks
+	handler.new_blocks(vec![], vec![], vec![h1], vec![], vec![], vec![], 0);
+	handler.new_blocks(vec![], vec![], vec![h2], vec![], vec![], vec![], 0);
+
+	// then a batch is pushed for each block, with local calls to the given address only
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(batch(res), vec![(hash(&first[0]), false)]);
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(batch(res), vec![(hash(&second[0]), false)]);
+
+	// when the second block is replaced by a reorg
+	handler.new_blocks(vec![], vec![], vec![h3], vec![h2], vec![], vec![], 0);
+
+	// then its return data is pushed again, marked as removed, before the replacement's
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(batch(res), vec![(hash(&second[0]), true)]);
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(batch(res), vec![(hash(&third[0]), false)]);
+
+	drop(metadata);
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
@@ -52,6 +52,22 @@ fn rpc_net_peer_count() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_net_peer_count_without_peers() {
+	let sync = Arc::new(TestSyncProvider::new(Config {
+		network_id: 3,
+		num_peers: 0,
+	}));
+	let net = NetClient::new(&sync).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(net);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "net_peerCount", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_net_listening() {
 	let sync = sync_provider();
@@ -64,3 +80,126 @@ fn rpc_net_listening() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_net_listening_rejects_params() {
+	use serde_json;
+
+	let sync = sync_provider();
+	let net = NetClient::new(&sync).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(net);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "net_listening", "params": [true], "id": 1}"#;
+	let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+
+	assert_eq!(response["result"], serde_json::Value::Null);
+	assert_eq!(response["error"]["code"], serde_json::Value::from(-32602));
+}
+
+#[test]
+fn rpc_net_version_and_peer_count_dispatch_separately() {
+	let sync = sync_provider();
+	let net = NetClient::new(&sync).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(net);
+
+	let request = r#"[{"jsonrpc": "2.0", "method": "net_peerCount", "params": [], "id": 1},{"jsonrpc": "2.0", "method": "net_version", "params": [], "id": 2}]"#;
+	let response = r#"[{"jsonrpc":"2.0","result":"0x78","id":1},{"jsonrpc":"2.0","result":"3","id":2}]"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_net_version_returns_configured_network_id() {
+	use serde_json;
+
+	let sync = Arc::new(TestSyncProvider::new(Config {
+		network_id: 1337,
+		num_peers: 120,
+	}));
+	let net = NetClient::new(&sync).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(net);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "net_version", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"1337","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "net_version", "params": [1], "id": 1}"#;
+	let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+	assert_eq!(response["result"], serde_json::Value::Null);
+	assert_eq!(response["error"]["code"], serde_json::Value::from(-32602));
+}
+
+#[test]
+fn rpc_net_peer_info() {
+	let sync = sync_provider();
+	let net = NetClient::new(&sync).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(net);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "net_peerInfo", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"caps":["eth/62","eth/63"],"id":"node1","name":"Parity/1","network":{"direction":"outbound","localAddress":"127.0.0.1:8888","remoteAddress":"127.0.0.1:7777"},"protocols":{"eth":{"difficulty":"0x28","head":"0000000000000000000000000000000000000000000000000000000000000032","version":62},"pip":null}},{"caps":["eth/63","eth/64"],"id":null,"name":"Parity/2","network":{"direction":"inbound","localAddress":"127.0.0.1:3333","remoteAddress":"Handshake"},"protocols":{"eth":{"difficulty":null,"head":"000000000000000000000000000000000000000000000000000000000000003c","version":64},"pip":null}}],"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_net_peer_latency() {
+	let sync = sync_provider();
+	{
+		let mut peers = sync.peers.write();
+		let peer = peers[0].clone();
+		*peers = [Some(30), None, Some(10), Some(20)].iter().map(|ping_ms| {
+			let mut peer = peer.clone();
+			peer.ping_ms = *ping_ms;
+			peer
+		}).collect();
+	}
+	let net = NetClient::new(&sync).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(net);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "net_peerLatency", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"max":30,"median":20,"min":10},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_net_peer_latency_without_peers() {
+	let sync = sync_provider();
+	sync.peers.write().clear();
+	let net = NetClient::new(&sync).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(net);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "net_peerLatency", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"max":null,"median":null,"min":null},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_net_sync_status() {
+	use ethsync::SyncState;
+
+	let sync = sync_provider();
+	{
+		let mut status = sync.status.write();
+		status.state = SyncState::Blocks;
+		status.num_active_peers = 2;
+	}
+	let net = NetClient::new(&sync).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(net);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "net_syncStatus", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"discovering":false,"enoughPeers":true,"syncing":true},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	sync.status.write().state = SyncState::WaitingPeers;
+	let response = r#"{"jsonrpc":"2.0","result":{"discovering":true,"enoughPeers":false,"syncing":false},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
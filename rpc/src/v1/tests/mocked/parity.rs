@@ -23,12 +23,13 @@ use ethstore::ethkey::{Generator, Random};
 use ethsync::ManageNetwork;
 use node_health::{self, NodeHealth};
 use parity_reactor;
+use parking_lot::Mutex;
 use ethereum_types::{Address, U256, H256};
 
 use jsonrpc_core::IoHandler;
 use v1::{Parity, ParityClient};
 use v1::metadata::Metadata;
-use v1::helpers::{SignerService, NetworkSettings};
+use v1::helpers::{SignerService, NetworkSettings, PollManager, SharedFilters};
 use v1::tests::helpers::{TestSyncProvider, Config, TestMinerService, TestUpdater};
 use super::manage_network::TestManageNetwork;
 use Host;
@@ -47,6 +48,7 @@ pub struct Dependencies {
 	pub accounts: Arc<AccountProvider>,
 	pub dapps_address: Option<Host>,
 	pub ws_address: Option<Host>,
+	pub filters: SharedFilters,
 }
 
 impl Dependencies {
@@ -77,6 +79,7 @@ impl Dependencies {
 			accounts: Arc::new(AccountProvider::transient_provider()),
 			dapps_address: Some("127.0.0.1:18080".into()),
 			ws_address: Some("127.0.0.1:18546".into()),
+			filters: Arc::new(Mutex::new(PollManager::new())),
 		}
 	}
 
@@ -96,6 +99,7 @@ impl Dependencies {
 			signer,
 			self.dapps_address.clone(),
 			self.ws_address.clone(),
+			self.filters.clone(),
 		)
 	}
 
@@ -332,7 +336,7 @@ fn rpc_parity_net_peers() {
 	let io = deps.default_client();
 
 	let request = r#"{"jsonrpc": "2.0", "method": "parity_netPeers", "params":[], "id": 1}"#;
-	let response = r#"{"jsonrpc":"2.0","result":{"active":0,"connected":120,"max":50,"peers":[{"caps":["eth/62","eth/63"],"id":"node1","name":"Parity/1","network":{"localAddress":"127.0.0.1:8888","remoteAddress":"127.0.0.1:7777"},"protocols":{"eth":{"difficulty":"0x28","head":"0000000000000000000000000000000000000000000000000000000000000032","version":62},"pip":null}},{"caps":["eth/63","eth/64"],"id":null,"name":"Parity/2","network":{"localAddress":"127.0.0.1:3333","remoteAddress":"Handshake"},"protocols":{"eth":{"difficulty":null,"head":"000000000000000000000000000000000000000000000000000000000000003c","version":64},"pip":null}}]},"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"active":0,"connected":120,"max":50,"peers":[{"caps":["eth/62","eth/63"],"id":"node1","name":"Parity/1","network":{"direction":"outbound","localAddress":"127.0.0.1:8888","remoteAddress":"127.0.0.1:7777"},"protocols":{"eth":{"difficulty":"0x28","head":"0000000000000000000000000000000000000000000000000000000000000032","version":62},"pip":null}},{"caps":["eth/63","eth/64"],"id":null,"name":"Parity/2","network":{"direction":"inbound","localAddress":"127.0.0.1:3333","remoteAddress":"Handshake"},"protocols":{"eth":{"difficulty":null,"head":"000000000000000000000000000000000000000000000000000000000000003c","version":64},"pip":null}}]},"id":1}"#;
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
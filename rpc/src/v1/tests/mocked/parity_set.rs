@@ -23,9 +23,11 @@ use ethcore::miner::MinerService;
 use ethcore::client::TestBlockChainClient;
 use ethsync::ManageNetwork;
 use futures_cpupool::CpuPool;
+use parking_lot::Mutex;
 
 use jsonrpc_core::IoHandler;
-use v1::{ParitySet, ParitySetClient};
+use v1::{EthFilter, EthFilterClient, ParitySet, ParitySetClient};
+use v1::helpers::{PollManager, SharedFilters};
 use v1::tests::helpers::{TestMinerService, TestFetch, TestUpdater, TestDappsService};
 use super::manage_network::TestManageNetwork;
 
@@ -52,10 +54,20 @@ fn parity_set_client(
 	miner: &Arc<TestMinerService>,
 	updater: &Arc<TestUpdater>,
 	net: &Arc<TestManageNetwork>,
+) -> TestParitySetClient {
+	parity_set_client_with_filters(client, miner, updater, net, Arc::new(Mutex::new(PollManager::new())))
+}
+
+fn parity_set_client_with_filters(
+	client: &Arc<TestBlockChainClient>,
+	miner: &Arc<TestMinerService>,
+	updater: &Arc<TestUpdater>,
+	net: &Arc<TestManageNetwork>,
+	filters: SharedFilters,
 ) -> TestParitySetClient {
 	let dapps_service = Arc::new(TestDappsService);
 	let pool = CpuPool::new(1);
-	ParitySetClient::new(client, miner, updater, &(net.clone() as Arc<ManageNetwork>), Some(dapps_service), TestFetch::default(), pool)
+	ParitySetClient::new(client, miner, updater, &(net.clone() as Arc<ManageNetwork>), Some(dapps_service), TestFetch::default(), pool, filters)
 }
 
 #[test]
@@ -255,3 +267,42 @@ fn rpc_parity_set_dapps_list() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_parity_uninstall_all_filters() {
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let filter_client = EthFilterClient::new(client.clone(), miner.clone());
+	let filters = filter_client.filters();
+
+	let mut io = IoHandler::new();
+	io.extend_with(filter_client.to_delegate());
+	io.extend_with(parity_set_client_with_filters(&client, &miner, &updater, &network, filters).to_delegate());
+
+	let requests = [
+		r#"{"jsonrpc": "2.0", "method": "eth_newFilter", "params": [{}], "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newBlockFilter", "id": 1}"#,
+		r#"{"jsonrpc": "2.0", "method": "eth_newPendingTransactionFilter", "id": 1}"#,
+	];
+	for (id, request) in requests.iter().enumerate() {
+		let response = format!(r#"{{"jsonrpc":"2.0","result":"0x{}","id":1}}"#, id);
+		assert_eq!(io.handle_request_sync(request), Some(response));
+	}
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_uninstallAllFilters", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x3","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	for id in 0..3 {
+		let request = format!(r#"{{"jsonrpc": "2.0", "method": "eth_getFilterChanges", "params": ["0x{}"], "id": 1}}"#, id);
+		let response = r#"{"jsonrpc":"2.0","error":{"code":-32080,"message":"Filter not found"},"id":1}"#;
+		assert_eq!(io.handle_request_sync(&request), Some(response.to_owned()));
+	}
+
+	// the method is not available in the eth namespace anymore
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_uninstallAllFilters", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -19,13 +19,20 @@ use std::sync::Arc;
 use ethcore::executed::{Executed, CallError};
 use ethcore::trace::trace::{Action, Res, Call};
 use ethcore::trace::LocalizedTrace;
-use ethcore::client::TestBlockChainClient;
+use ethcore::client::{TestBlockChainClient, EachBlockWith, ImportBlock};
+use ethcore::header::Header;
+use ethkey::{Generator, Random};
+use futures_cpupool::CpuPool;
+use rlp::RlpStream;
+use transaction::{Transaction, Action, SignedTransaction};
 
 use vm::CallType;
 
 use jsonrpc_core::IoHandler;
+use jsonrpc_core::futures::Future;
 use v1::tests::helpers::{TestMinerService};
 use v1::{Metadata, Traces, TracesClient};
+use v1::types::BlockNumber;
 
 struct Tester {
 	client: Arc<TestBlockChainClient>,
@@ -66,7 +73,7 @@ fn io() -> Tester {
 		state_diff: None,
 	}));
 	let miner = Arc::new(TestMinerService::default());
-	let traces = TracesClient::new(&client);
+	let traces = TracesClient::new(&client, CpuPool::new(1));
 	let mut io = IoHandler::default();
 	io.extend_with(traces.to_delegate());
 
@@ -242,3 +249,114 @@ fn rpc_trace_replay_block_transactions() {
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+fn import_block_with_calls(client: &TestBlockChainClient, recipients: &[u64]) -> Vec<SignedTransaction> {
+	let keypair = Random.generate().unwrap();
+	let transactions = recipients.iter().enumerate().map(|(nonce, recipient)| Transaction {
+		nonce: (nonce as u64).into(),
+		gas_price: 0.into(),
+		gas: 100_000.into(),
+		action: Action::Call((*recipient).into()),
+		value: 0.into(),
+		data: vec![],
+	}.sign(keypair.secret(), None)).collect::<Vec<_>>();
+
+	let mut header = Header::new();
+	header.set_number(1);
+	header.set_parent_hash(*client.last_hash.read());
+	let mut block = RlpStream::new_list(3);
+	block.append(&header);
+	block.append_list::<SignedTransaction, _>(&transactions);
+	block.begin_list(0);
+	client.import_block(block.out()).unwrap();
+
+	transactions
+}
+
+#[test]
+fn rpc_trace_replay_block_return_data() {
+	let tester = io();
+	let transactions = import_block_with_calls(&tester.client, &[5, 5]);
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_replayBlockReturnData","params":["0x1"],"id":1}"#;
+	let response = format!(
+		r#"{{"jsonrpc":"2.0","result":[{{"removed":false,"returnData":"010203","transactionHash":"0x{:x}","transactionIndex":"0x0"}},{{"removed":false,"returnData":"010203","transactionHash":"0x{:x}","transactionIndex":"0x1"}}],"id":1}}"#,
+		transactions[0].hash(),
+		transactions[1].hash(),
+	);
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_trace_replay_block_return_data_without_blocking() {
+	let tester = io();
+	let transactions = import_block_with_calls(&tester.client, &[5]);
+	let traces = TracesClient::new(&tester.client, CpuPool::new(1));
+
+	// replaying can't complete while the execution result is locked,
+	// so the call only returns because the replay is done on the pool
+	let return_data = {
+		let _lock = tester.client.execution_result.write();
+		traces.replay_block_return_data(BlockNumber::Num(1), None.into())
+	};
+
+	let return_data = return_data.wait().unwrap();
+	assert_eq!(return_data.len(), 1);
+	assert_eq!(return_data[0].transaction_hash, transactions[0].hash());
+	assert_eq!(return_data[0].transaction_index, Some(0u64.into()));
+	assert_eq!(return_data[0].return_data, "010203");
+}
+
+#[test]
+fn rpc_trace_replay_block_return_data_for_addresses() {
+	let tester = io();
+	let transactions = import_block_with_calls(&tester.client, &[5, 6]);
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_replayBlockReturnData","params":["0x1", ["0x0000000000000000000000000000000000000006"]],"id":1}"#;
+	let response = format!(
+		r#"{{"jsonrpc":"2.0","result":[{{"removed":false,"returnData":"010203","transactionHash":"0x{:x}","transactionIndex":"0x1"}}],"id":1}}"#,
+		transactions[1].hash(),
+	);
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_trace_replay_block_receipts() {
+	let tester = io();
+	let transactions = import_block_with_calls(&tester.client, &[5, 6]);
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_replayBlockReceipts","params":["0x1"],"id":1}"#;
+	let response = format!(
+		r#"{{"jsonrpc":"2.0","result":[{{"gasUsed":"0x2710","output":"0x010203","transactionHash":"0x{:x}","transactionIndex":"0x0"}},{{"gasUsed":"0x2710","output":"0x010203","transactionHash":"0x{:x}","transactionIndex":"0x1"}}],"id":1}}"#,
+		transactions[0].hash(),
+		transactions[1].hash(),
+	);
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_trace_replay_block_receipts_state_pruned() {
+	let tester = io();
+	tester.client.add_blocks(1, EachBlockWith::Transaction);
+	*tester.client.execution_result.write() = Some(Err(CallError::StatePruned));
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_replayBlockReceipts","params":["0x1"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_trace_replay_block_return_data_state_pruned() {
+	let tester = io();
+	tester.client.add_blocks(1, EachBlockWith::Transaction);
+	*tester.client.execution_result.write() = Some(Err(CallError::StatePruned));
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_replayBlockReturnData","params":["0x1"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
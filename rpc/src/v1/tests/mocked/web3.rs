@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
 use jsonrpc_core::IoHandler;
 use version::version;
 use v1::{Web3, Web3Client};
@@ -32,6 +33,26 @@ fn rpc_web3_version() {
 	assert_eq!(io.handle_request_sync(request), Some(response));
 }
 
+#[test]
+fn rpc_web3_version_with_capabilities() {
+	let modules: BTreeMap<String, String> = vec![("eth", "1.0"), ("web3", "1.0")].into_iter()
+		.map(|(name, version)| (name.to_owned(), version.to_owned()))
+		.collect();
+	let web3 = Web3Client::with_capabilities(modules, vec!["filters".to_owned()]).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(web3);
+
+	let v = version().to_owned().replace("Parity/", "Parity//");
+
+	let request = r#"{"jsonrpc": "2.0", "method": "web3_clientVersion", "params": [true], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"features":["filters"],"namespaces":["eth","web3"],"version":"VER"},"id":1}"#.to_owned().replace("VER", v.as_ref());
+	assert_eq!(io.handle_request_sync(request), Some(response));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "web3_clientVersion", "params": [false], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"VER","id":1}"#.to_owned().replace("VER", v.as_ref());
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
+
 #[test]
 fn rpc_web3_sha3() {
 	let web3 = Web3Client::new().to_delegate();
@@ -55,3 +76,46 @@ fn rpc_web3_sha3_wiki() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_web3_sha3_empty() {
+	let web3 = Web3Client::new().to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(web3);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "web3_sha3", "params": ["0x"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_web3_sha3_invalid_params() {
+	let web3 = Web3Client::new().to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(web3);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "web3_sha3", "params": ["0x123"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params: Invalid bytes format. Expected a 0x-prefixed hex string with even length."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "web3_sha3", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Invalid params: invalid length 0, expected a tuple of size 1."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_web3_batch() {
+	let web3 = Web3Client::new().to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(web3);
+
+	let v = version().to_owned().replace("Parity/", "Parity//");
+
+	let request = r#"[{"jsonrpc": "2.0", "method": "web3_clientVersion", "params": [], "id": 1},{"jsonrpc": "2.0", "method": "web3_sha3", "params": ["0x00"], "id": 2}]"#;
+	let response = r#"[{"jsonrpc":"2.0","result":"VER","id":1},{"jsonrpc":"2.0","result":"0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a","id":2}]"#.to_owned().replace("VER", v.as_ref());
+
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
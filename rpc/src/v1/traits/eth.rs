@@ -15,12 +15,14 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Eth rpc interface.
+use std::collections::BTreeMap;
+
 use jsonrpc_core::{Result, BoxFuture};
 use jsonrpc_macros::Trailing;
 
-use v1::types::{RichBlock, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Index};
-use v1::types::{Log, Receipt, SyncStatus, Transaction, Work};
-use v1::types::{H64, H160, H256, U256};
+use v1::types::{BatchFilterChanges, RichBlock, BlockNumber, Bytes, CallRequest, Filter, FilterChanges, FilterState, FilterLogs, Index};
+use v1::types::{Log, PendingTransactionFilterOptions, Receipt, RichHeader, SyncStatus, Transaction, Work};
+use v1::types::{H64, H160, H256, U64, U256};
 
 build_rpc_trait! {
 	/// Eth rpc interface.
@@ -31,6 +33,11 @@ build_rpc_trait! {
 		#[rpc(name = "eth_protocolVersion")]
 		fn protocol_version(&self) -> Result<String>;
 
+		/// Returns the EIP-155 chain id used for signing transactions at the current block,
+		/// or `null` if replay protection is not enabled yet.
+		#[rpc(name = "eth_chainId")]
+		fn chain_id(&self) -> Result<Option<U64>>;
+
 		/// Returns an object with data about the sync status or false. (wtf?)
 		#[rpc(name = "eth_syncing")]
 		fn syncing(&self) -> Result<SyncStatus>;
@@ -186,23 +193,95 @@ build_rpc_trait! {
 		fn new_filter(&self, Filter) -> Result<U256>;
 
 		/// Returns id of new block filter.
+		/// If a non-zero limit is passed, a single poll returns at most that many hashes,
+		/// the remaining ones are returned by the following polls.
 		#[rpc(name = "eth_newBlockFilter")]
-		fn new_block_filter(&self) -> Result<U256>;
-
-		/// Returns id of new block filter.
+		fn new_block_filter(&self, Trailing<usize>) -> Result<U256>;
+
+		/// Returns id of new block filter returning full block headers.
+		#[rpc(name = "eth_newBlockHeaderFilter")]
+		fn new_block_header_filter(&self) -> Result<U256>;
+
+		/// Returns id of new pending transaction filter.
+		/// If `true` is passed, full transactions are returned instead of hashes.
+		/// Alternatively `{"full": bool, "limit": number, "newestFirst": bool, "markReturned": bool}`
+		/// may be passed, in which case a single poll returns at most `limit` transactions and the
+		/// remaining ones are returned by the following polls. With `newestFirst` the most recently
+		/// queued transactions are returned first. With `markReturned` polls return
+		/// `{"hash", "returned"}` objects, `returned` telling whether the transaction was mined
+		/// before and a reorganisation returned it to the pool.
 		#[rpc(name = "eth_newPendingTransactionFilter")]
-		fn new_pending_transaction_filter(&self) -> Result<U256>;
+		fn new_pending_transaction_filter(&self, Trailing<PendingTransactionFilterOptions>) -> Result<U256>;
 
 		/// Returns filter changes since last poll.
 		#[rpc(name = "eth_getFilterChanges")]
 		fn filter_changes(&self, Index) -> BoxFuture<FilterChanges>;
 
+		/// Returns changes of all given filters since their last poll, keyed by filter id.
+		/// Filters which can't be polled, e.g. because they don't exist, are mapped
+		/// to an error instead of failing the whole request.
+		#[rpc(name = "parity_getFilterChangesBatch")]
+		fn filter_changes_batch(&self, Vec<Index>) -> BoxFuture<BTreeMap<String, BatchFilterChanges>>;
+
+		/// Returns changes of those given filters which are of given type since their last poll,
+		/// keyed by filter id, like `parity_getFilterChangesBatch`. Filters of other types are left out.
+		/// The type is one of the types reported by `parity_listFilters`.
+		#[rpc(name = "parity_getFilterChangesByType")]
+		fn filter_changes_by_type(&self, String, Vec<Index>) -> BoxFuture<BTreeMap<String, BatchFilterChanges>>;
+
 		/// Returns all logs matching given filter (in a range 'from' - 'to').
 		#[rpc(name = "eth_getFilterLogs")]
 		fn filter_logs(&self, Index) -> BoxFuture<Vec<Log>>;
 
-		/// Uninstalls filter.
+		/// Returns all logs matching given filter together with the numbers of the first
+		/// and the last block scanned, with `latest` and `pending` resolved at query time.
+		/// `truncated` is set if logs were left out to respect the node's maximal result size.
+		#[rpc(name = "parity_getFilterLogsWithRange")]
+		fn filter_logs_with_range(&self, Index) -> BoxFuture<FilterLogs>;
+
+		/// Returns logs matching given filter from the given block on, like `parity_getFilterLogsWithRange`.
+		/// Lets clients resume from a block they know instead of the cursor stored by the node,
+		/// which is left untouched. Block hash filters ignore the given block.
+		#[rpc(name = "parity_getFilterChangesSince")]
+		fn filter_changes_since(&self, Index, BlockNumber) -> BoxFuture<FilterLogs>;
+
+		/// Returns logs matching given filter from the first block mined at or after the given
+		/// UNIX timestamp on, like `parity_getFilterChangesSince`.
+		#[rpc(name = "parity_getFilterChangesSinceTimestamp")]
+		fn filter_changes_since_timestamp(&self, Index, u64) -> BoxFuture<FilterLogs>;
+
+		/// Uninstalls filter. Fails if there is no filter with given id.
 		#[rpc(name = "eth_uninstallFilter")]
 		fn uninstall_filter(&self, Index) -> Result<bool>;
+
+		/// Returns filter changes since last poll and uninstalls the filter,
+		/// so that no changes are missed between the last poll and the removal.
+		#[rpc(name = "parity_drainFilter")]
+		fn drain_filter(&self, Index) -> BoxFuture<FilterChanges>;
+
+		/// Returns the number of pending transactions, without installing a filter.
+		#[rpc(name = "eth_pendingTransactionCount")]
+		fn pending_transaction_count(&self) -> Result<U256>;
+
+		/// Returns the header of the latest block which is unlikely to be reorged,
+		/// the one `safe` filter bounds refer to. `null` if the node doesn't track it.
+		#[rpc(name = "eth_getSafeBlock")]
+		fn safe_block(&self) -> Result<Option<RichHeader>>;
+
+		/// Returns the header of the latest block which can't be reorged anymore,
+		/// the one `finalized` filter bounds refer to. `null` if the node doesn't track finality.
+		#[rpc(name = "eth_getFinalizedBlock")]
+		fn finalized_block(&self) -> Result<Option<RichHeader>>;
+
+		/// Returns whether given filter would match given log, without installing the filter.
+		/// Only numeric bounds of the filter restrict the block range of mined logs.
+		#[rpc(name = "parity_testFilter")]
+		fn test_filter(&self, Filter, Log) -> Result<bool>;
+
+		/// Installs a filter exported by `parity_exportFilter`, resuming from its cursor.
+		/// Returns id of the new filter. Pending logs reported before the export may be
+		/// reported again, while pending transaction filters start from the current pool.
+		#[rpc(name = "parity_importFilter")]
+		fn import_filter(&self, FilterState) -> Result<U256>;
 	}
 }
@@ -17,6 +17,8 @@
 //! Net rpc interface.
 use jsonrpc_core::Result;
 
+use v1::types::{NetSyncStatus, PeerInfo, PeerLatency};
+
 build_rpc_trait! {
 	/// Net rpc interface.
 	pub trait Net {
@@ -32,5 +34,20 @@ build_rpc_trait! {
 		/// Otherwise false.
 		#[rpc(name = "net_listening")]
 		fn is_listening(&self) -> Result<bool>;
+
+		/// Returns detailed information about connected peers.
+		#[rpc(name = "net_peerInfo")]
+		fn peer_info(&self) -> Result<Vec<PeerInfo>>;
+
+		/// Returns the shortest, median and longest ping round-trip times of connected peers.
+		/// All of them are `null` if no peer answered a ping yet.
+		#[rpc(name = "net_peerLatency")]
+		fn peer_latency(&self) -> Result<PeerLatency>;
+
+		/// Returns whether the node is still collecting peers, whether it has enough peers
+		/// to sync and whether it is syncing. Unlike `eth_syncing` the block queue is not
+		/// taken into account.
+		#[rpc(name = "net_syncStatus")]
+		fn sync_status(&self) -> Result<NetSyncStatus>;
 	}
 }
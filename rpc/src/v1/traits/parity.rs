@@ -29,6 +29,7 @@ use v1::types::{
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
 	AccountInfo, HwAccountInfo, RichHeader,
+	Index, FilterInfo, FilterState, FilterStats,
 };
 
 build_rpc_trait! {
@@ -221,5 +222,25 @@ build_rpc_trait! {
 		/// Returns node's health report.
 		#[rpc(name = "parity_nodeHealth")]
 		fn node_health(&self) -> BoxFuture<Health>;
+
+		/// Returns ids, types and idle times of all installed filters.
+		/// Listing filters doesn't count as polling them.
+		#[rpc(name = "parity_listFilters")]
+		fn list_filters(&self) -> Result<Vec<FilterInfo>>;
+
+		/// Returns the type, creation block and, for log filters, the criteria of the filter.
+		/// Doesn't count as polling the filter.
+		#[rpc(name = "parity_getFilterInfo")]
+		fn filter_info(&self, Index) -> Result<FilterInfo>;
+
+		/// Returns the number of installed filters, in total and by filter type.
+		/// Doesn't count as polling the filters.
+		#[rpc(name = "parity_filterStats")]
+		fn filter_stats(&self) -> Result<FilterStats>;
+
+		/// Returns the type, criteria and cursor of given filter, e.g. to install it again
+		/// with `parity_importFilter` after the node restarts. Doesn't count as a poll.
+		#[rpc(name = "parity_exportFilter")]
+		fn export_filter(&self, Index) -> Result<FilterState>;
 	}
 }
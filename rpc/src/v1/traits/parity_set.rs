@@ -119,5 +119,11 @@ build_rpc_trait! {
 		/// Returns `true` when transaction was removed, `false` if it was not found.
 		#[rpc(name = "parity_removeTransaction")]
 		fn remove_transaction(&self, H256) -> Result<Option<Transaction>>;
+
+		/// Uninstalls all filters and returns the number of filters removed.
+		/// Filters are shared by all connections served by the filter API instance,
+		/// so this also removes filters installed by other clients.
+		#[rpc(name = "parity_uninstallAllFilters")]
+		fn uninstall_all_filters(&self) -> Result<U256>;
 	}
 }
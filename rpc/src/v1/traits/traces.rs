@@ -16,9 +16,10 @@
 
 //! Traces specific rpc interface.
 
-use jsonrpc_core::Result;
+use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_macros::Trailing;
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, H256, TraceOptions};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, ReplayedReceipt, TraceResults, H160, H256, TraceOptions};
+use v1::types::pubsub::ReturnData;
 
 build_rpc_trait! {
 	/// Traces specific rpc interface.
@@ -60,5 +61,15 @@ build_rpc_trait! {
 		/// Executes all the transactions at the given block and returns a number of possible traces for each transaction.
 		#[rpc(name = "trace_replayBlockTransactions")]
 		fn replay_block_transactions(&self, BlockNumber, TraceOptions) ->  Result<Vec<TraceResults>>;
+
+		/// Executes all the transactions at the given block and returns the data returned by each transaction.
+		/// If addresses are given, only transactions sent to one of them are executed.
+		#[rpc(name = "trace_replayBlockReturnData")]
+		fn replay_block_return_data(&self, BlockNumber, Trailing<Vec<H160>>) -> BoxFuture<Vec<ReturnData>>;
+
+		/// Executes all the transactions at the given block and returns the hash, gas used
+		/// and output of each transaction.
+		#[rpc(name = "trace_replayBlockReceipts")]
+		fn replay_block_receipts(&self, BlockNumber) -> BoxFuture<Vec<ReplayedReceipt>>;
 	}
 }
@@ -16,15 +16,18 @@
 
 //! Web3 rpc interface.
 use jsonrpc_core::Result;
+use jsonrpc_macros::Trailing;
 
-use v1::types::{H256, Bytes};
+use v1::types::{H256, Bytes, ClientVersion};
 
 build_rpc_trait! {
 	/// Web3 rpc interface.
 	pub trait Web3 {
 		/// Returns current client version.
+		/// If `true` is passed, returns an object with the version, enabled namespaces
+		/// and features instead, so that clients can detect what is available.
 		#[rpc(name = "web3_clientVersion")]
-		fn client_version(&self) -> Result<String>;
+		fn client_version(&self, Trailing<bool>) -> Result<ClientVersion>;
 
 		/// Returns sha3 of the given data
 		#[rpc(name = "web3_sha3")]
@@ -18,6 +18,7 @@ use std::fmt;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::{Error, Visitor};
 use ethcore::client::BlockId;
+use v1::helpers::errors;
 
 /// Represents rpc api block number param.
 #[derive(Debug, PartialEq, Clone, Hash, Eq)]
@@ -30,6 +31,10 @@ pub enum BlockNumber {
 	Earliest,
 	/// Pending block (being mined)
 	Pending,
+	/// Latest block unlikely to be reorged
+	Safe,
+	/// Latest block which can't be reorged anymore
+	Finalized,
 }
 
 impl Default for BlockNumber {
@@ -61,6 +66,8 @@ impl Serialize for BlockNumber {
 			BlockNumber::Latest => serializer.serialize_str("latest"),
 			BlockNumber::Earliest => serializer.serialize_str("earliest"),
 			BlockNumber::Pending => serializer.serialize_str("pending"),
+			BlockNumber::Safe => serializer.serialize_str("safe"),
+			BlockNumber::Finalized => serializer.serialize_str("finalized"),
 		}
 	}
 }
@@ -71,7 +78,7 @@ impl<'a> Visitor<'a> for BlockNumberVisitor {
 	type Value = BlockNumber;
 
 	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-		write!(formatter, "a block number or 'latest', 'earliest' or 'pending'")
+		write!(formatter, "a block number or 'latest', 'earliest', 'pending', 'safe' or 'finalized'")
 	}
 
 	fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> where E: Error {
@@ -79,6 +86,8 @@ impl<'a> Visitor<'a> for BlockNumberVisitor {
 			"latest" => Ok(BlockNumber::Latest),
 			"earliest" => Ok(BlockNumber::Earliest),
 			"pending" => Ok(BlockNumber::Pending),
+			"safe" => Ok(BlockNumber::Safe),
+			"finalized" => Ok(BlockNumber::Finalized),
 			_ if value.starts_with("0x") => u64::from_str_radix(&value[2..], 16).map(BlockNumber::Num).map_err(|e| {
 				Error::custom(format!("Invalid block number: {}", e))
 			}),
@@ -91,14 +100,17 @@ impl<'a> Visitor<'a> for BlockNumberVisitor {
 	}
 }
 
-/// Converts `BlockNumber` to `BlockId`, panics on `BlockNumber::Pending`
-pub fn block_number_to_id(number: BlockNumber) -> BlockId {
+/// Converts `BlockNumber` to `BlockId`, panics on `BlockNumber::Pending`.
+/// `BlockNumber::Safe` and `BlockNumber::Finalized` are rejected with `unsupported_block_tag`,
+/// they can only be resolved by clients which know about finality.
+pub fn block_number_to_id(number: BlockNumber) -> Result<BlockId, ::jsonrpc_core::Error> {
 	match number {
-		BlockNumber::Num(num) => BlockId::Number(num),
-		BlockNumber::Earliest => BlockId::Earliest,
-		BlockNumber::Latest => BlockId::Latest,
+		BlockNumber::Num(num) => Ok(BlockId::Number(num)),
+		BlockNumber::Earliest => Ok(BlockId::Earliest),
+		BlockNumber::Latest => Ok(BlockId::Latest),
 
-		BlockNumber::Pending => panic!("`BlockNumber::Pending` should be handled manually")
+		BlockNumber::Pending => panic!("`BlockNumber::Pending` should be handled manually"),
+		BlockNumber::Safe | BlockNumber::Finalized => Err(errors::unsupported_block_tag()),
 	}
 }
 
@@ -115,6 +127,20 @@ mod tests {
 		assert_eq!(deserialized, vec![BlockNumber::Num(10), BlockNumber::Latest, BlockNumber::Earliest, BlockNumber::Pending])
 	}
 
+	#[test]
+	fn block_tag_deserialization() {
+		let s = r#"["safe", "finalized"]"#;
+		let deserialized: Vec<BlockNumber> = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, vec![BlockNumber::Safe, BlockNumber::Finalized]);
+		assert_eq!(serde_json::to_string(&deserialized).unwrap(), r#"["safe","finalized"]"#);
+	}
+
+	#[test]
+	fn should_not_deserialize_unknown_tag() {
+		let s = r#""justified""#;
+		assert!(serde_json::from_str::<BlockNumber>(s).is_err());
+	}
+
 	#[test]
 	fn should_not_deserialize_decimal() {
 		let s = r#""10""#;
@@ -123,16 +149,22 @@ mod tests {
 
 	#[test]
 	fn normal_block_number_to_id() {
-		assert_eq!(block_number_to_id(BlockNumber::Num(100)), BlockId::Number(100));
-		assert_eq!(block_number_to_id(BlockNumber::Earliest), BlockId::Earliest);
-		assert_eq!(block_number_to_id(BlockNumber::Latest), BlockId::Latest);
+		assert_eq!(block_number_to_id(BlockNumber::Num(100)), Ok(BlockId::Number(100)));
+		assert_eq!(block_number_to_id(BlockNumber::Earliest), Ok(BlockId::Earliest));
+		assert_eq!(block_number_to_id(BlockNumber::Latest), Ok(BlockId::Latest));
 	}
 
 	#[test]
 	#[should_panic]
 	fn pending_block_number_to_id() {
 		// Since this function is not allowed to be called in such way, panic should happen
-		block_number_to_id(BlockNumber::Pending);
+		let _ = block_number_to_id(BlockNumber::Pending);
+	}
+
+	#[test]
+	fn finalized_block_number_to_id() {
+		assert!(block_number_to_id(BlockNumber::Safe).is_err());
+		assert!(block_number_to_id(BlockNumber::Finalized).is_err());
 	}
 }
 
@@ -0,0 +1,67 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Client version with enabled capabilities.
+
+use serde::{Serialize, Serializer};
+
+/// Result of the `web3_clientVersion` RPC.
+#[derive(Debug, PartialEq)]
+pub enum ClientVersion {
+	/// Plain version string.
+	Version(String),
+	/// Version together with enabled capabilities.
+	Detailed(ClientCapabilities),
+}
+
+impl Serialize for ClientVersion {
+	fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error> where S: Serializer {
+		match *self {
+			ClientVersion::Version(ref version) => version.serialize(s),
+			ClientVersion::Detailed(ref capabilities) => capabilities.serialize(s),
+		}
+	}
+}
+
+/// Client version and the capabilities enabled on the endpoint.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ClientCapabilities {
+	/// Version string, as returned by the plain form
+	pub version: String,
+	/// Enabled RPC namespaces
+	pub namespaces: Vec<String>,
+	/// Features provided by the enabled namespaces
+	pub features: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::{ClientVersion, ClientCapabilities};
+
+	#[test]
+	fn client_version_serialization() {
+		let plain = ClientVersion::Version("Parity//v1.10.0".into());
+		let detailed = ClientVersion::Detailed(ClientCapabilities {
+			version: "Parity//v1.10.0".into(),
+			namespaces: vec!["eth".into()],
+			features: vec!["filters".into()],
+		});
+
+		assert_eq!(serde_json::to_string(&plain).unwrap(), r#""Parity//v1.10.0""#);
+		assert_eq!(serde_json::to_string(&detailed).unwrap(), r#"{"version":"Parity//v1.10.0","namespaces":["eth"],"features":["filters"]}"#);
+	}
+}
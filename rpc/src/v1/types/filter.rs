@@ -14,12 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::{Error, DeserializeOwned};
 use serde_json::{Value, from_value};
 use ethcore::filter::Filter as EthFilter;
 use ethcore::client::BlockId;
-use v1::types::{BlockNumber, H160, H256, Log};
+use v1::helpers::errors;
+use v1::types::{BlockNumber, H160, H256, U256, Log, RichHeader, Transaction};
 
 /// Variadic value
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -41,9 +44,32 @@ impl<'a, T> Deserialize<'a> for VariadicValue<T> where T: DeserializeOwned {
 			return Ok(VariadicValue::Null);
 		}
 
-		from_value(v.clone()).map(VariadicValue::Single)
-			.or_else(|_| from_value(v).map(VariadicValue::Multiple))
-			.map_err(|err| D::Error::custom(format!("Invalid variadic value type: {}", err)))
+		let parse = |value: Value| match value.is_array() {
+			// arrays of arrays are malformed rather than flattened
+			true => Err(format!("Invalid variadic value {}: expected null, a single value or a flat array", value)),
+			false => from_value(value.clone()).map_err(|err| format!("Invalid variadic value {}: {}", value, err)),
+		};
+
+		// values are parsed one by one, so that the malformed one can be named
+		let parsed = match v {
+			Value::Array(values) => values.into_iter()
+				.map(parse)
+				.collect::<Result<Vec<T>, _>>()
+				.map(VariadicValue::Multiple),
+			value => parse(value).map(VariadicValue::Single),
+		};
+
+		parsed.map_err(D::Error::custom)
+	}
+}
+
+impl<T> Serialize for VariadicValue<T> where T: DeserializeOwned + Serialize {
+	fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error> where S: Serializer {
+		match *self {
+			VariadicValue::Single(ref value) => value.serialize(s),
+			VariadicValue::Multiple(ref values) => values.serialize(s),
+			VariadicValue::Null => s.serialize_none(),
+		}
 	}
 }
 
@@ -52,8 +78,28 @@ pub type FilterAddress = VariadicValue<H160>;
 /// Topic
 pub type Topic = VariadicValue<H256>;
 
+/// End of the matching logs which is kept when their number is limited.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+pub enum LimitDirection {
+	/// Keep the most recent logs
+	#[serde(rename="last")]
+	Last,
+	/// Keep the oldest logs
+	#[serde(rename="first")]
+	First,
+}
+
+impl Default for LimitDirection {
+	fn default() -> Self {
+		LimitDirection::Last
+	}
+}
+
+/// Maximal number of topic positions, as logs have at most four topics.
+const MAX_TOPICS: usize = 4;
+
 /// Filter
-#[derive(Debug, PartialEq, Clone, Deserialize, Eq, Hash)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Hash)]
 #[serde(deny_unknown_fields)]
 pub struct Filter {
 	/// From Block
@@ -62,25 +108,134 @@ pub struct Filter {
 	/// To Block
 	#[serde(rename="toBlock")]
 	pub to_block: Option<BlockNumber>,
+	/// Block hash, mutually exclusive with `fromBlock` and `toBlock`
+	#[serde(rename="blockHash", skip_serializing_if = "Option::is_none")]
+	pub block_hash: Option<H256>,
 	/// Address
 	pub address: Option<FilterAddress>,
 	/// Topics
 	pub topics: Option<Vec<Topic>>,
 	/// Limit
 	pub limit: Option<usize>,
+	/// Exclude logs from the pending block, even if the range reaches `pending`
+	#[serde(rename="excludePending", skip_serializing_if = "Option::is_none")]
+	pub exclude_pending: Option<bool>,
+	/// Whether `limit` keeps the last (default) or the first logs
+	#[serde(rename="limitDirection", skip_serializing_if = "Option::is_none")]
+	pub limit_direction: Option<LimitDirection>,
+	/// Transaction hash, only logs of this transaction match.
+	/// Mutually exclusive with `fromBlock`, `toBlock` and `blockHash`
+	#[serde(rename="transactionHash", skip_serializing_if = "Option::is_none")]
+	pub transaction_hash: Option<H256>,
+	/// Block hashes, logs of each of these blocks match.
+	/// Mutually exclusive with `fromBlock`, `toBlock`, `blockHash` and `transactionHash`
+	#[serde(rename="blockHashes", skip_serializing_if = "Option::is_none")]
+	pub block_hashes: Option<Vec<H256>>,
 }
 
-impl Into<EthFilter> for Filter {
-	fn into(self) -> EthFilter {
-		let num_to_id = |num| match num {
-			BlockNumber::Num(n) => BlockId::Number(n),
-			BlockNumber::Earliest => BlockId::Earliest,
-			BlockNumber::Latest | BlockNumber::Pending => BlockId::Latest,
+impl Filter {
+	/// Checks whether logs from the pending block should be included,
+	/// which is the case if either bound of the range is `pending`
+	/// and pending logs are not explicitly excluded.
+	pub fn includes_pending(&self) -> bool {
+		let reaches_pending = self.from_block == Some(BlockNumber::Pending) || self.to_block == Some(BlockNumber::Pending);
+		reaches_pending && !self.exclude_pending.unwrap_or(false)
+	}
+
+	/// Returns the end of the matching logs kept by `limit`.
+	pub fn limit_direction(&self) -> LimitDirection {
+		self.limit_direction.unwrap_or_default()
+	}
+
+	/// Checks whether a topic position is an empty set of alternatives,
+	/// in which case no log can ever match the filter.
+	pub fn matches_nothing(&self) -> bool {
+		self.topics.as_ref().map_or(false, |topics| topics.iter().any(|topic| match *topic {
+			VariadicValue::Multiple(ref topics) => topics.is_empty(),
+			_ => false,
+		}))
+	}
+
+	/// Makes sure that `blockHash`, `blockHashes` and `transactionHash` are not combined with a block range
+	/// and that there are at most four topic positions.
+	/// `safe` and `finalized` bounds are rejected, so APIs supporting them
+	/// have to resolve them to block numbers first.
+	pub fn validate(&self) -> Result<(), ::jsonrpc_core::Error> {
+		let is_tag = |number: &Option<BlockNumber>| match *number {
+			Some(BlockNumber::Safe) | Some(BlockNumber::Finalized) => true,
+			_ => false,
+		};
+		if is_tag(&self.from_block) || is_tag(&self.to_block) {
+			return Err(errors::unsupported_block_tag());
+		}
+
+		if self.block_hash.is_some() && (self.from_block.is_some() || self.to_block.is_some()) {
+			return Err(errors::invalid_params(
+				"blockHash",
+				"blockHash is mutually exclusive with fromBlock and toBlock",
+			));
+		}
+
+		if self.transaction_hash.is_some() && (self.block_hash.is_some() || self.from_block.is_some() || self.to_block.is_some()) {
+			return Err(errors::invalid_params(
+				"transactionHash",
+				"transactionHash is mutually exclusive with blockHash, fromBlock and toBlock",
+			));
+		}
+
+		if self.block_hashes.is_some() && (self.block_hash.is_some() || self.transaction_hash.is_some() || self.from_block.is_some() || self.to_block.is_some()) {
+			return Err(errors::invalid_params(
+				"blockHashes",
+				"blockHashes is mutually exclusive with blockHash, transactionHash, fromBlock and toBlock",
+			));
+		}
+
+		if self.topics.as_ref().map_or(false, |topics| topics.len() > MAX_TOPICS) {
+			return Err(errors::invalid_params("topics", "too many topic positions"));
+		}
+
+		Ok(())
+	}
+
+	/// Converts the filter into the client's log filter.
+	/// Fails if the filter can't be represented by it: `safe` and `finalized` bounds
+	/// have to be resolved beforehand, there may be at most four topic positions,
+	/// and logs of a transaction or of a set of blocks have to be looked up separately.
+	pub fn try_into_eth_filter(self) -> Result<EthFilter, ::jsonrpc_core::Error> {
+		if self.topics.as_ref().map_or(false, |topics| topics.len() > MAX_TOPICS) {
+			return Err(errors::invalid_params("topics", "too many topic positions"));
+		}
+		if self.transaction_hash.is_some() {
+			return Err(errors::invalid_params("transactionHash", "logs of a transaction can't be filtered by a block range"));
+		}
+		if self.block_hashes.is_some() {
+			return Err(errors::invalid_params("blockHashes", "logs of a set of blocks can't be filtered by a block range"));
+		}
+
+		let num_to_id = |num: Option<BlockNumber>| match num {
+			Some(BlockNumber::Num(n)) => Ok(BlockId::Number(n)),
+			Some(BlockNumber::Earliest) => Ok(BlockId::Earliest),
+			Some(BlockNumber::Latest) | Some(BlockNumber::Pending) | None => Ok(BlockId::Latest),
+			Some(BlockNumber::Safe) | Some(BlockNumber::Finalized) => Err(errors::unsupported_block_tag()),
+		};
+
+		let (from_block, to_block) = match self.block_hash {
+			Some(hash) => {
+				let block = BlockId::Hash(hash.into());
+				(block.clone(), block)
+			},
+			None => (num_to_id(self.from_block)?, num_to_id(self.to_block)?),
+		};
+
+		// the client keeps the most recent logs, so the first ones have to be limited afterwards
+		let limit = match self.limit_direction() {
+			LimitDirection::Last => self.limit,
+			LimitDirection::First => None,
 		};
 
-		EthFilter {
-			from_block: self.from_block.map_or_else(|| BlockId::Latest, &num_to_id),
-			to_block: self.to_block.map_or_else(|| BlockId::Latest, &num_to_id),
+		Ok(EthFilter {
+			from_block: from_block,
+			to_block: to_block,
 			address: self.address.and_then(|address| match address {
 				VariadicValue::Null => None,
 				VariadicValue::Single(a) => Some(vec![a.into()]),
@@ -100,11 +255,192 @@ impl Into<EthFilter> for Filter {
 					iter.next().unwrap_or(None)
 				]
 			},
-			limit: self.limit,
+			limit: limit,
+		})
+	}
+}
+
+/// Pending transaction filter options.
+///
+/// Either a boolean telling whether full transactions should be returned
+/// instead of hashes, or an object with optional `full`, `limit`, `newestFirst`
+/// and `markReturned` fields.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct PendingTransactionFilterOptions {
+	/// Whether full transactions should be returned instead of hashes
+	pub full: bool,
+	/// Maximal number of transactions returned by a single poll
+	pub limit: Option<usize>,
+	/// Whether the most recently queued transactions should be returned first,
+	/// so that `limit` keeps the newest ones
+	pub newest_first: bool,
+	/// Whether hashes should be flagged when the transaction was mined before
+	/// and a reorganisation returned it to the pool, can't be combined with `full`
+	pub mark_returned: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PendingTransactionFilterObject {
+	full: Option<bool>,
+	limit: Option<usize>,
+	#[serde(rename="newestFirst")]
+	newest_first: Option<bool>,
+	#[serde(rename="markReturned")]
+	mark_returned: Option<bool>,
+}
+
+impl<'a> Deserialize<'a> for PendingTransactionFilterOptions {
+	fn deserialize<D>(deserializer: D) -> Result<PendingTransactionFilterOptions, D::Error>
+	where D: Deserializer<'a> {
+		let v: Value = Deserialize::deserialize(deserializer)?;
+
+		if let Value::Bool(full) = v {
+			return Ok(PendingTransactionFilterOptions { full: full, limit: None, newest_first: false, mark_returned: false });
 		}
+
+		let options = from_value(v)
+			.map(|options: PendingTransactionFilterObject| PendingTransactionFilterOptions {
+				full: options.full.unwrap_or(false),
+				limit: options.limit,
+				newest_first: options.newest_first.unwrap_or(false),
+				mark_returned: options.mark_returned.unwrap_or(false),
+			})
+			.map_err(|err| D::Error::custom(format!("Invalid pending transaction filter options: {}", err)))?;
+
+		if options.full && options.mark_returned {
+			return Err(D::Error::custom("Invalid pending transaction filter options: markReturned can't be combined with full"));
+		}
+
+		Ok(options)
 	}
 }
 
+/// Hash of a new pending transaction, flagged if the transaction was mined before
+/// and a reorganisation returned it to the pool.
+#[derive(Debug, Serialize, PartialEq, Clone)]
+pub struct PendingTransactionHash {
+	/// Transaction hash
+	pub hash: H256,
+	/// Whether the transaction was returned to the pool by a reorganisation
+	pub returned: bool,
+}
+
+/// State of an installed filter, as exported by `parity_exportFilter` and installed again
+/// by `parity_importFilter`.
+///
+/// Only the filter type, its criteria and its cursor are kept. State only meaningful
+/// to the exporting node, like reported pending logs and transactions or recently
+/// reported block hashes, is rebuilt by the importing node.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(tag="type")]
+pub enum FilterState {
+	/// Block filter
+	#[serde(rename="block")]
+	Block {
+		/// Number of the next block to report
+		#[serde(rename="nextBlock")]
+		next_block: U256,
+		/// Maximal number of hashes returned by a single poll
+		limit: Option<usize>,
+	},
+	/// Block header filter
+	#[serde(rename="blockHeaders")]
+	BlockHeaders {
+		/// Number of the next block to report
+		#[serde(rename="nextBlock")]
+		next_block: U256,
+	},
+	/// Pending transaction filter
+	#[serde(rename="pendingTransaction")]
+	PendingTransaction {
+		/// Whether full transactions are returned instead of hashes
+		full: bool,
+		/// Maximal number of transactions returned by a single poll
+		limit: Option<usize>,
+		/// Whether the most recently queued transactions are returned first
+		#[serde(rename="newestFirst")]
+		newest_first: bool,
+		/// Whether hashes are flagged when a reorganisation returned the transaction to the pool
+		#[serde(rename="markReturned")]
+		mark_returned: bool,
+	},
+	/// Log filter
+	#[serde(rename="logs")]
+	Logs {
+		/// Log filter criteria
+		filter: Filter,
+		/// Number of the first block the next poll retrieves logs from
+		#[serde(rename="nextBlock")]
+		next_block: U256,
+	},
+}
+
+/// Information about an installed filter.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FilterInfo {
+	/// Filter id
+	pub id: U256,
+	/// Filter type
+	#[serde(rename="type")]
+	pub filter_type: String,
+	/// Seconds since the filter was last polled
+	#[serde(rename="idleSeconds")]
+	pub idle_seconds: u64,
+	/// Best block at the time the filter was created
+	#[serde(rename="createdAt")]
+	pub created_at: U256,
+	/// Log filter criteria, for log filters only
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub filter: Option<Filter>,
+}
+
+/// Number of installed filters, in total and by filter type.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FilterStats {
+	/// Number of all installed filters
+	pub total: U256,
+	/// Number of installed filters of each type
+	#[serde(rename="byType")]
+	pub by_type: BTreeMap<String, U256>,
+}
+
+/// Changes of a single filter polled by `parity_getFilterChangesBatch`,
+/// or the error polling it on its own would have failed with.
+#[derive(Debug, Serialize, PartialEq)]
+pub enum BatchFilterChanges {
+	/// Changes since the last poll
+	#[serde(rename="result")]
+	Changes(FilterChanges),
+	/// Error, e.g. the filter doesn't exist
+	#[serde(rename="error")]
+	Error(::jsonrpc_core::Error),
+}
+
+/// Logs of a filter together with the range of blocks they were retrieved from.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FilterLogs {
+	/// First block scanned, `None` if the block of a block hash filter is unknown
+	#[serde(rename="fromBlock")]
+	pub from_block: Option<U256>,
+	/// Last block scanned, `None` if the block of a block hash filter is unknown
+	#[serde(rename="toBlock")]
+	pub to_block: Option<U256>,
+	/// Matching logs
+	pub logs: Vec<Log>,
+	/// Number of all matching logs, `logs` is truncated if it's greater than `limit`
+	#[serde(rename="totalCount")]
+	pub total_count: U256,
+	/// Whether logs within `limit` were left out to keep the response within
+	/// the maximal result size of the node, omitted if not
+	#[serde(skip_serializing_if="is_false")]
+	pub truncated: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+	!*value
+}
+
 /// Results of the filter_changes RPC.
 #[derive(Debug, PartialEq)]
 pub enum FilterChanges {
@@ -112,6 +448,12 @@ pub enum FilterChanges {
 	Logs(Vec<Log>),
 	/// New hashes (block or transactions)
 	Hashes(Vec<H256>),
+	/// New block headers.
+	Headers(Vec<RichHeader>),
+	/// New pending transactions.
+	Transactions(Vec<Transaction>),
+	/// New pending transaction hashes, flagged if returned to the pool by a reorganisation.
+	PendingHashes(Vec<PendingTransactionHash>),
 	/// Empty result,
 	Empty,
 }
@@ -121,6 +463,9 @@ impl Serialize for FilterChanges {
 		match *self {
 			FilterChanges::Logs(ref logs) => logs.serialize(s),
 			FilterChanges::Hashes(ref hashes) => hashes.serialize(s),
+			FilterChanges::Headers(ref headers) => headers.serialize(s),
+			FilterChanges::Transactions(ref transactions) => transactions.serialize(s),
+			FilterChanges::PendingHashes(ref hashes) => hashes.serialize(s),
 			FilterChanges::Empty => (&[] as &[Value]).serialize(s),
 		}
 	}
@@ -131,11 +476,26 @@ mod tests {
 	use serde_json;
 	use std::str::FromStr;
 	use ethereum_types::H256;
-	use super::{VariadicValue, Topic, Filter};
+	use super::{VariadicValue, Topic, Filter, FilterChanges, PendingTransactionFilterOptions};
 	use v1::types::BlockNumber;
 	use ethcore::filter::Filter as EthFilter;
 	use ethcore::client::BlockId;
 
+	#[test]
+	fn empty_filter_changes_serialization() {
+		let empty = vec![
+			FilterChanges::Logs(vec![]),
+			FilterChanges::Hashes(vec![]),
+			FilterChanges::Headers(vec![]),
+			FilterChanges::Transactions(vec![]),
+			FilterChanges::Empty,
+		];
+
+		for changes in empty {
+			assert_eq!(serde_json::to_string(&changes).unwrap(), "[]");
+		}
+	}
+
 	#[test]
 	fn topic_deserialization() {
 		let s = r#"["0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b", null, ["0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b", "0x0000000000000000000000000aff3454fce5edbc8cca8697c15331677e6ebccc"]]"#;
@@ -157,9 +517,14 @@ mod tests {
 		assert_eq!(deserialized, Filter {
 			from_block: Some(BlockNumber::Earliest),
 			to_block: Some(BlockNumber::Latest),
+			block_hash: None,
 			address: None,
 			topics: None,
 			limit: None,
+			exclude_pending: None,
+			limit_direction: None,
+			transaction_hash: None,
+			block_hashes: None,
 		});
 	}
 
@@ -168,6 +533,7 @@ mod tests {
 		let filter = Filter {
 			from_block: Some(BlockNumber::Earliest),
 			to_block: Some(BlockNumber::Latest),
+			block_hash: None,
 			address: Some(VariadicValue::Multiple(vec![])),
 			topics: Some(vec![
 				VariadicValue::Null,
@@ -175,9 +541,13 @@ mod tests {
 				VariadicValue::Null,
 			]),
 			limit: None,
+			exclude_pending: None,
+			limit_direction: None,
+			transaction_hash: None,
+			block_hashes: None,
 		};
 
-		let eth_filter: EthFilter = filter.into();
+		let eth_filter = filter.try_into_eth_filter().unwrap();
 		assert_eq!(eth_filter, EthFilter {
 			from_block: BlockId::Earliest,
 			to_block: BlockId::Latest,
@@ -191,4 +561,132 @@ mod tests {
 			limit: None,
 		});
 	}
+
+	#[test]
+	fn filter_block_hash_conversion() {
+		let s = r#"{"blockHash":"0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b"}"#;
+		let filter: Filter = serde_json::from_str(s).unwrap();
+		assert!(filter.validate().is_ok());
+
+		let hash = H256::from_str("000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b").unwrap();
+		let eth_filter = filter.try_into_eth_filter().unwrap();
+		assert_eq!(eth_filter.from_block, BlockId::Hash(hash));
+		assert_eq!(eth_filter.to_block, BlockId::Hash(hash));
+	}
+
+	#[test]
+	fn filter_topic_positions_are_limited() {
+		let four = r#"{"topics":[null,null,null,null]}"#;
+		let five = r#"{"topics":[null,null,null,null,null]}"#;
+
+		assert!(serde_json::from_str::<Filter>(four).unwrap().validate().is_ok());
+		assert!(serde_json::from_str::<Filter>(five).unwrap().validate().is_err());
+	}
+
+	#[test]
+	fn filter_conversion_fails_if_filter_cant_be_represented() {
+		let convert = |s: &str| serde_json::from_str::<Filter>(s).unwrap().try_into_eth_filter().map(|_| ());
+		let hash = "0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b";
+
+		assert_eq!(convert(r#"{"topics":[null,null,null,null]}"#), Ok(()));
+		assert!(convert(r#"{"topics":[null,null,null,null,null]}"#).is_err());
+		assert!(convert(r#"{"toBlock":"safe"}"#).is_err());
+		assert!(convert(&format!(r#"{{"transactionHash":"{}"}}"#, hash)).is_err());
+		assert!(convert(&format!(r#"{{"blockHashes":["{}"]}}"#, hash)).is_err());
+	}
+
+	#[test]
+	fn topic_positions_must_be_flat() {
+		let filter = |s: &str| serde_json::from_str::<Filter>(s).map_err(|err| err.to_string());
+		let topic = "0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b";
+
+		let flat = filter(&format!(r#"{{"topics":[["{}","{}"],null]}}"#, topic, topic)).unwrap();
+		assert_eq!(flat.topics, Some(vec![
+			VariadicValue::Multiple(vec![H256::from_str(&topic[2..]).unwrap().into(); 2]),
+			VariadicValue::Null,
+		]));
+
+		let nested = filter(&format!(r#"{{"topics":[[["{}"],"{}"]]}}"#, topic, topic)).unwrap_err();
+		assert!(nested.contains("expected null, a single value or a flat array"), "{}", nested);
+	}
+
+	#[test]
+	fn filter_addresses_must_be_well_formed() {
+		let filter = |s: &str| serde_json::from_str::<Filter>(s).map_err(|err| err.to_string());
+		let address = "0x0000000000000000000000000000000000000005";
+
+		assert!(filter(&format!(r#"{{"address":"{}"}}"#, address)).is_ok());
+		assert!(filter(&format!(r#"{{"address":["{}","0x00000000000000000000000000000000000000aB"]}}"#, address)).is_ok());
+
+		let too_short = filter(&format!(r#"{{"address":["{}","0x05"]}}"#, address)).unwrap_err();
+		assert!(too_short.contains(r#""0x05""#), "{}", too_short);
+
+		let not_hex = filter(r#"{"address":"0x000000000000000000000000000000000000000g"}"#).unwrap_err();
+		assert!(not_hex.contains(r#""0x000000000000000000000000000000000000000g""#), "{}", not_hex);
+	}
+
+	#[test]
+	fn filter_block_hash_with_range_is_invalid() {
+		let s = r#"{"fromBlock":"earliest","blockHash":"0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b"}"#;
+		let filter: Filter = serde_json::from_str(s).unwrap();
+		assert!(filter.validate().is_err());
+	}
+
+	#[test]
+	fn filter_topic_wildcards() {
+		use ethcore::log_entry::LogEntry;
+
+		let topic = |n: u64| format!("0x{:?}", H256::from(n));
+		let log = |topics: &[u64]| LogEntry {
+			address: Default::default(),
+			topics: topics.iter().map(|n| H256::from(*n)).collect(),
+			data: vec![],
+		};
+		let logs = vec![
+			log(&[1, 2, 3]),
+			log(&[1, 5, 4]),
+			log(&[1, 2, 5]),
+			log(&[2, 2, 3]),
+			log(&[1]),
+			log(&[1, 2]),
+		];
+		let matching = |topics: String| -> Vec<usize> {
+			let filter: Filter = serde_json::from_str(&format!(r#"{{"topics":{}}}"#, topics)).unwrap();
+			let filter = filter.try_into_eth_filter().unwrap();
+			logs.iter().enumerate().filter(|&(_, log)| filter.matches(log)).map(|(i, _)| i).collect()
+		};
+
+		// interior wildcard
+		assert_eq!(matching(format!(r#"["{}",null,["{}","{}"]]"#, topic(1), topic(3), topic(4))), vec![0, 1]);
+		// leading wildcard
+		assert_eq!(matching(format!(r#"[null,"{}"]"#, topic(2))), vec![0, 2, 3, 5]);
+		// trailing wildcards, explicit and omitted
+		assert_eq!(matching(format!(r#"["{}",null,null]"#, topic(1))), vec![0, 1, 2, 4, 5]);
+		assert_eq!(matching(format!(r#"["{}"]"#, topic(1))), vec![0, 1, 2, 4, 5]);
+	}
+
+	#[test]
+	fn filter_serialization_roundtrip() {
+		let s = r#"{"fromBlock":"0x1","toBlock":"latest","address":["0x0000000000000000000000000000000000000005"],"topics":[null,"0x000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b"],"limit":null}"#;
+		let filter: Filter = serde_json::from_str(s).unwrap();
+		assert_eq!(serde_json::to_string(&filter).unwrap(), s);
+
+		let s = r#"{"fromBlock":null,"toBlock":null,"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000005","address":null,"topics":null,"limit":null}"#;
+		let filter: Filter = serde_json::from_str(s).unwrap();
+		assert_eq!(serde_json::to_string(&filter).unwrap(), s);
+	}
+
+	#[test]
+	fn pending_transaction_filter_options_deserialization() {
+		let options = |s: &str| serde_json::from_str::<PendingTransactionFilterOptions>(s);
+
+		assert_eq!(options("true").unwrap(), PendingTransactionFilterOptions { full: true, limit: None, newest_first: false, mark_returned: false });
+		assert_eq!(options("{}").unwrap(), PendingTransactionFilterOptions { full: false, limit: None, newest_first: false, mark_returned: false });
+		assert_eq!(options(r#"{"full":true,"limit":10}"#).unwrap(), PendingTransactionFilterOptions { full: true, limit: Some(10), newest_first: false, mark_returned: false });
+		assert_eq!(options(r#"{"limit":2,"newestFirst":true}"#).unwrap(), PendingTransactionFilterOptions { full: false, limit: Some(2), newest_first: true, mark_returned: false });
+		assert_eq!(options(r#"{"markReturned":true}"#).unwrap(), PendingTransactionFilterOptions { full: false, limit: None, newest_first: false, mark_returned: true });
+		assert!(options(r#"{"full":true,"markReturned":true}"#).is_err());
+		assert!(options(r#"{"limits":10}"#).is_err());
+		assert!(options("10").is_err());
+	}
 }
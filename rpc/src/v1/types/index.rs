@@ -19,7 +19,7 @@ use serde::{Deserialize, Deserializer};
 use serde::de::{Error, Visitor};
 
 /// Represents usize.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Index(usize);
 
 impl Index {
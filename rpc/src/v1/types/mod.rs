@@ -22,6 +22,7 @@ mod block;
 mod block_number;
 mod bytes;
 mod call_request;
+mod client_version;
 mod confirmations;
 mod consensus_status;
 mod dapps;
@@ -52,6 +53,7 @@ pub use self::bytes::Bytes;
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
 pub use self::block_number::{BlockNumber, block_number_to_id};
 pub use self::call_request::CallRequest;
+pub use self::client_version::{ClientVersion, ClientCapabilities};
 pub use self::confirmations::{
 	ConfirmationPayload, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken,
 	TransactionModification, SignRequest, DecryptRequest, Either
@@ -59,7 +61,7 @@ pub use self::confirmations::{
 pub use self::consensus_status::*;
 pub use self::dapps::LocalDapp;
 pub use self::derivation::{DeriveHash, DeriveHierarchical, Derive};
-pub use self::filter::{Filter, FilterChanges};
+pub use self::filter::{BatchFilterChanges, Filter, FilterChanges, FilterInfo, FilterLogs, FilterState, FilterStats, LimitDirection, PendingTransactionFilterOptions, PendingTransactionHash};
 pub use self::hash::{H64, H160, H256, H512, H520, H2048};
 pub use self::histogram::Histogram;
 pub use self::index::Index;
@@ -70,10 +72,10 @@ pub use self::receipt::Receipt;
 pub use self::rpc_settings::RpcSettings;
 pub use self::secretstore::EncryptedDocumentKey;
 pub use self::sync::{
-	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
+	SyncStatus, SyncInfo, Peers, PeerInfo, PeerLatency, NetSyncStatus, PeerNetworkInfo, PeerProtocolsInfo,
 	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
 };
-pub use self::trace::{LocalizedTrace, TraceResults};
+pub use self::trace::{LocalizedTrace, ReplayedReceipt, TraceResults};
 pub use self::trace_filter::TraceFilter;
 pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus};
 pub use self::transaction_request::TransactionRequest;
@@ -19,20 +19,90 @@
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
 use serde_json::{Value, from_value};
-use v1::types::{RichHeader, Filter, Log, H256};
+use v1::types::{RichHeader, Filter, Log, Transaction, H160, H256, U256};
 use ethereum_types::H256 as Eth256;
 
 #[derive(Debug, Serialize, PartialEq, Eq, Hash, Clone)]
 pub struct ReturnData {
 	#[serde(rename="transactionHash")]
 	pub transaction_hash: Eth256,
+	#[serde(rename="transactionIndex")]
+	pub transaction_index: Option<U256>,
 	#[serde(rename="returnData")]
 	pub return_data: String,
 	pub removed: bool,
+	/// Whether `return_data` is gzip-compressed and base64-encoded instead of hex-encoded.
+	#[serde(skip_serializing_if="is_false")]
+	pub compressed: bool,
+	/// Encoding of `return_data`, omitted if hex.
+	#[serde(skip_serializing_if="ReturnDataEncoding::is_hex")]
+	pub encoding: ReturnDataEncoding,
+}
+
+/// Return data of transactions of a block, as notified to a subscriber.
+#[derive(Debug, Serialize, PartialEq, Eq, Clone)]
+pub struct ReturnDataBatch {
+	/// Return data of the transactions, in block order.
+	pub transactions: Vec<ReturnData>,
+	/// Whether return data of further transactions of the block were left out
+	/// to keep the notification within `maxSizePerBlock`, omitted if not.
+	#[serde(skip_serializing_if="is_false")]
+	pub truncated: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+	!*value
+}
+
+/// Encoding of the return data output.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ReturnDataEncoding {
+	/// Hex-encoded output.
+	#[serde(rename="hex")]
+	Hex,
+	/// Base64-encoded output, more compact for large outputs.
+	#[serde(rename="base64")]
+	Base64,
+}
+
+impl ReturnDataEncoding {
+	fn is_hex(&self) -> bool {
+		*self == ReturnDataEncoding::Hex
+	}
+}
+
+impl Default for ReturnDataEncoding {
+	fn default() -> Self {
+		ReturnDataEncoding::Hex
+	}
+}
+
+/// Return data subscription options.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Hash, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReturnDataOptions {
+	/// Whether return data should be gzip-compressed and base64-encoded.
+	#[serde(default)]
+	pub compress: bool,
+	/// Encoding of uncompressed return data, hex by default.
+	#[serde(default)]
+	pub encoding: ReturnDataEncoding,
+	/// Only local transactions are replayed, restricted to those
+	/// sent to these addresses if given.
+	#[serde(default)]
+	pub addresses: Option<Vec<H160>>,
+	/// Maximal number of return data entries notified for a single block, 256 by default.
+	/// Transactions beyond it are not replayed.
+	#[serde(rename="maxPerBlock", default)]
+	pub max_per_block: Option<usize>,
+	/// Maximal serialized size of return data notified for a single block, in bytes.
+	/// Entries which don't fit are left out and the notification is marked as truncated.
+	#[serde(rename="maxSizePerBlock", default)]
+	pub max_size_per_block: Option<usize>,
 }
 
 /// Subscription result.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Result {
 	/// New block header.
 	Header(RichHeader),
@@ -40,8 +110,10 @@ pub enum Result {
 	Log(Log),
 	/// Transaction hash
 	TransactionHash(H256),
-	/// Transaction return data
-	ReturnData(ReturnData)
+	/// Full transaction
+	FullTransaction(Transaction),
+	/// Return data of transactions of a block
+	ReturnData(ReturnDataBatch)
 }
 
 impl Serialize for Result {
@@ -52,7 +124,8 @@ impl Serialize for Result {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Log(ref log) => log.serialize(serializer),
 			Result::TransactionHash(ref hash) => hash.serialize(serializer),
-			Result::ReturnData(ref data) => data.serialize(serializer),
+			Result::FullTransaction(ref transaction) => transaction.serialize(serializer),
+			Result::ReturnData(ref batch) => batch.serialize(serializer),
 		}
 	}
 }
@@ -85,6 +158,11 @@ pub enum Params {
 	None,
 	/// Log parameters.
 	Logs(Filter),
+	/// Pending transactions parameters: whether full transactions should be returned.
+	/// Light clients only support notifications with hashes.
+	Transactions(bool),
+	/// Return data parameters.
+	ReturnData(ReturnDataOptions),
 }
 
 impl Default for Params {
@@ -102,6 +180,16 @@ impl<'a> Deserialize<'a> for Params {
 			return Ok(Params::None);
 		}
 
+		if let Some(full) = v.as_bool() {
+			return Ok(Params::Transactions(full));
+		}
+
+		let is_return_data = ["compress", "encoding", "addresses", "maxPerBlock", "maxSizePerBlock"].iter().any(|key| v.get(key).is_some());
+		if is_return_data {
+			return from_value(v.clone()).map(Params::ReturnData)
+				.map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)));
+		}
+
 		from_value(v.clone()).map(Params::Logs)
 			.map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)))
 	}
@@ -110,7 +198,7 @@ impl<'a> Deserialize<'a> for Params {
 #[cfg(test)]
 mod tests {
 	use serde_json;
-	use super::{Result, Kind, Params};
+	use super::{Result, Kind, Params, ReturnData, ReturnDataBatch, ReturnDataEncoding, ReturnDataOptions};
 	use v1::types::{RichHeader, Header, Filter};
 	use v1::types::filter::VariadicValue;
 
@@ -123,6 +211,34 @@ mod tests {
 		assert_eq!(serde_json::from_str::<Kind>(r#""returnData""#).unwrap(), Kind::ReturnData);
 	}
 
+	#[test]
+	fn should_deserialize_transactions() {
+		assert_eq!(serde_json::from_str::<Params>(r#"true"#).unwrap(), Params::Transactions(true));
+		assert_eq!(serde_json::from_str::<Params>(r#"false"#).unwrap(), Params::Transactions(false));
+	}
+
+	#[test]
+	fn should_deserialize_return_data_options() {
+		assert_eq!(
+			serde_json::from_str::<Params>(r#"{"compress":true}"#).unwrap(),
+			Params::ReturnData(ReturnDataOptions { compress: true, ..Default::default() })
+		);
+		assert_eq!(
+			serde_json::from_str::<Params>(r#"{"encoding":"base64"}"#).unwrap(),
+			Params::ReturnData(ReturnDataOptions { encoding: ReturnDataEncoding::Base64, ..Default::default() })
+		);
+		assert_eq!(
+			serde_json::from_str::<Params>(r#"{"addresses":["0x0000000000000000000000000000000000000005"],"maxPerBlock":2}"#).unwrap(),
+			Params::ReturnData(ReturnDataOptions { addresses: Some(vec![5.into()]), max_per_block: Some(2), ..Default::default() })
+		);
+		assert_eq!(
+			serde_json::from_str::<Params>(r#"{"maxSizePerBlock":1024}"#).unwrap(),
+			Params::ReturnData(ReturnDataOptions { max_size_per_block: Some(1024), ..Default::default() })
+		);
+		assert!(serde_json::from_str::<Params>(r#"{"encoding":"base32"}"#).is_err());
+		assert!(serde_json::from_str::<Params>(r#"{"compress":true,"limit":10}"#).is_err());
+	}
+
 	#[test]
 	fn should_deserialize_logs() {
 		let none = serde_json::from_str::<Params>(r#"null"#).unwrap();
@@ -136,25 +252,40 @@ mod tests {
 		assert_eq!(logs1, Params::Logs(Filter {
 			from_block: None,
 			to_block: None,
+			block_hash: None,
 			address: None,
 			topics: None,
 			limit: None,
+			exclude_pending: None,
+			limit_direction: None,
+			transaction_hash: None,
+			block_hashes: None,
 		}));
 		assert_eq!(logs2, Params::Logs(Filter {
 			from_block: None,
 			to_block: None,
+			block_hash: None,
 			address: None,
 			topics: None,
 			limit: Some(10),
+			exclude_pending: None,
+			limit_direction: None,
+			transaction_hash: None,
+			block_hashes: None,
 		}));
 		assert_eq!(logs3, Params::Logs(Filter {
 			from_block: None,
 			to_block: None,
+			block_hash: None,
 			address: None,
 			topics: Some(vec![
 				VariadicValue::Single("000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b".parse().unwrap()
 			)]),
 			limit: None,
+			exclude_pending: None,
+			limit_direction: None,
+			transaction_hash: None,
+			block_hashes: None,
 		}));
 	}
 
@@ -185,4 +316,36 @@ mod tests {
 		let expected = r#"{"author":"0x0000000000000000000000000000000000000000","difficulty":"0x0","extraData":"0x","gasLimit":"0x0","gasUsed":"0x0","hash":"0x0000000000000000000000000000000000000000000000000000000000000000","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","miner":"0x0000000000000000000000000000000000000000","number":"0x0","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","receiptsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","sealFields":["0x","0x"],"sha3Uncles":"0x0000000000000000000000000000000000000000000000000000000000000000","size":"0x45","stateRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","timestamp":"0x0","transactionsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000"}"#;
 		assert_eq!(serde_json::to_string(&header).unwrap(), expected);
 	}
+
+	#[test]
+	fn should_serialize_return_data() {
+		let data = ReturnData {
+			transaction_hash: 5.into(),
+			transaction_index: Some(17u64.into()),
+			return_data: "0102".into(),
+			removed: false,
+			compressed: false,
+			encoding: ReturnDataEncoding::Hex,
+		};
+		let batch = |transactions: Vec<ReturnData>, truncated: bool| Result::ReturnData(ReturnDataBatch {
+			transactions: transactions,
+			truncated: truncated,
+		});
+
+		assert_eq!(
+			serde_json::to_string(&batch(vec![data.clone()], false)).unwrap(),
+			r#"{"transactions":[{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000005","transactionIndex":"0x11","returnData":"0102","removed":false}]}"#
+		);
+
+		let compressed = ReturnData { compressed: true, encoding: ReturnDataEncoding::Base64, ..data.clone() };
+		assert_eq!(
+			serde_json::to_string(&batch(vec![compressed], false)).unwrap(),
+			r#"{"transactions":[{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000005","transactionIndex":"0x11","returnData":"0102","removed":false,"compressed":true,"encoding":"base64"}]}"#
+		);
+
+		assert_eq!(
+			serde_json::to_string(&batch(vec![data], true)).unwrap(),
+			r#"{"transactions":[{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000005","transactionIndex":"0x11","returnData":"0102","removed":false}],"truncated":true}"#
+		);
+	}
 }
@@ -70,6 +70,8 @@ pub struct PeerInfo {
 /// Peer network information
 #[derive(Default, Debug, Serialize)]
 pub struct PeerNetworkInfo {
+	/// Direction of the connection, `inbound` or `outbound` when originated by this node
+	pub direction: String,
 	/// Remote endpoint address
 	#[serde(rename="remoteAddress")]
 	pub remote_address: String,
@@ -166,6 +168,10 @@ impl From<SyncPeerInfo> for PeerInfo {
 			name: p.client_version,
 			caps: p.capabilities,
 			network: PeerNetworkInfo {
+				direction: match p.originated {
+					true => "outbound",
+					false => "inbound",
+				}.into(),
 				remote_address: p.remote_address,
 				local_address: p.local_address,
 			},
@@ -177,6 +183,67 @@ impl From<SyncPeerInfo> for PeerInfo {
 	}
 }
 
+/// Round-trip times of pings to connected peers, in milliseconds.
+/// Peers which haven't answered a ping yet are not taken into account.
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct PeerLatency {
+	/// Shortest round-trip time
+	pub min: Option<u64>,
+	/// Median round-trip time, mean of the two middle ones for an even number of peers
+	pub median: Option<u64>,
+	/// Longest round-trip time
+	pub max: Option<u64>,
+}
+
+impl<'a> From<&'a [SyncPeerInfo]> for PeerLatency {
+	fn from(peers: &'a [SyncPeerInfo]) -> Self {
+		let mut pings: Vec<u64> = peers.iter().filter_map(|peer| peer.ping_ms).collect();
+		pings.sort();
+
+		let len = pings.len();
+		if len == 0 {
+			return PeerLatency::default();
+		}
+
+		PeerLatency {
+			min: Some(pings[0]),
+			median: Some(match len % 2 {
+				0 => (pings[len / 2 - 1] + pings[len / 2]) / 2,
+				_ => pings[len / 2],
+			}),
+			max: Some(pings[len - 1]),
+		}
+	}
+}
+
+/// Peer-related sync status of the node.
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct NetSyncStatus {
+	/// Whether sync is still collecting peers before it can start
+	pub discovering: bool,
+	/// Whether the node is connected to enough peers to sync from them
+	#[serde(rename="enoughPeers")]
+	pub enough_peers: bool,
+	/// Whether blocks or a snapshot are being downloaded
+	pub syncing: bool,
+}
+
+impl From<ethsync::SyncStatus> for NetSyncStatus {
+	fn from(status: ethsync::SyncStatus) -> Self {
+		use ethsync::SyncState;
+
+		let discovering = status.state == SyncState::WaitingPeers;
+		NetSyncStatus {
+			discovering: discovering,
+			enough_peers: !discovering && status.num_peers > 0,
+			syncing: match status.state {
+				SyncState::WaitingPeers | SyncState::Idle | SyncState::NewBlocks => false,
+				_ => true,
+			},
+		}
+	}
+}
+
 impl From<SyncTransactionStats> for TransactionStats {
 	fn from(s: SyncTransactionStats) -> Self {
 		TransactionStats {
@@ -624,6 +624,22 @@ impl From<Executed> for TraceResults {
 	}
 }
 
+/// Outcome of a replayed transaction.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ReplayedReceipt {
+	/// Transaction hash
+	#[serde(rename="transactionHash")]
+	pub transaction_hash: H256,
+	/// Position of the transaction in its block
+	#[serde(rename="transactionIndex")]
+	pub transaction_index: U256,
+	/// Gas used by the transaction
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+	/// The output of the call/create
+	pub output: Bytes,
+}
+
 #[cfg(test)]
 mod tests {
 	use serde_json;
@@ -18,6 +18,7 @@
 
 use ethcore::client::BlockId;
 use ethcore::client;
+use v1::helpers::errors;
 use v1::types::{BlockNumber, H160};
 
 /// Trace filter
@@ -42,6 +43,18 @@ pub struct TraceFilter {
 	pub count: Option<usize>,
 }
 
+impl TraceFilter {
+	/// Makes sure that the range is not bounded by `safe` or `finalized` blocks,
+	/// which are not tracked by trace filters.
+	pub fn validate(&self) -> Result<(), ::jsonrpc_core::Error> {
+		match (self.from_block.as_ref(), self.to_block.as_ref()) {
+			(Some(&BlockNumber::Safe), _) | (Some(&BlockNumber::Finalized), _) |
+			(_, Some(&BlockNumber::Safe)) | (_, Some(&BlockNumber::Finalized)) => Err(errors::unsupported_block_tag()),
+			_ => Ok(()),
+		}
+	}
+}
+
 impl Into<client::TraceFilter> for TraceFilter {
 	fn into(self) -> client::TraceFilter {
 		let num_to_id = |num| match num {
@@ -51,7 +64,9 @@ impl Into<client::TraceFilter> for TraceFilter {
 			BlockNumber::Pending => {
 				warn!("Pending traces are not supported and might be removed in future versions. Falling back to Latest");
 				BlockId::Latest
-			}
+			},
+			// rejected by `validate`
+			BlockNumber::Safe | BlockNumber::Finalized => BlockId::Latest,
 		};
 		let start = self.from_block.map_or(BlockId::Latest, &num_to_id);
 		let end = self.to_block.map_or(BlockId::Latest, &num_to_id);
@@ -106,7 +106,7 @@ pub struct TransactionStats {
 }
 
 /// Peer connection information
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PeerInfo {
 	/// Public node id
 	pub id: Option<String>,
@@ -118,14 +118,18 @@ pub struct PeerInfo {
 	pub remote_address: String,
 	/// Local endpoint address
 	pub local_address: String,
+	/// True if the connection was originated by this node
+	pub originated: bool,
 	/// Eth protocol info.
 	pub eth_info: Option<EthProtocolInfo>,
 	/// Light protocol info.
 	pub pip_info: Option<PipProtocolInfo>,
+	/// Round-trip time of the last ping in milliseconds, if any
+	pub ping_ms: Option<u64>,
 }
 
 /// Ethereum protocol info.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EthProtocolInfo {
 	/// Protocol version
 	pub version: u32,
@@ -136,7 +140,7 @@ pub struct EthProtocolInfo {
 }
 
 /// PIP protocol info.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PipProtocolInfo {
 	/// Protocol version
 	pub version: u32,
@@ -299,8 +303,10 @@ impl SyncProvider for EthSync {
 					capabilities: session_info.peer_capabilities.into_iter().map(|c| c.to_string()).collect(),
 					remote_address: session_info.remote_address,
 					local_address: session_info.local_address,
+					originated: session_info.originated,
 					eth_info: eth_sync.peer_info(&peer_id),
 					pip_info: light_proto.as_ref().and_then(|lp| lp.peer_status(&peer_id)).map(Into::into),
+					ping_ms: session_info.ping_ms,
 				})
 			}).collect()
 		}).unwrap_or_else(Vec::new)
@@ -827,8 +833,10 @@ impl LightSyncProvider for LightSync {
 					capabilities: session_info.peer_capabilities.into_iter().map(|c| c.to_string()).collect(),
 					remote_address: session_info.remote_address,
 					local_address: session_info.local_address,
+					originated: session_info.originated,
 					eth_info: None,
 					pip_info: self.proto.peer_status(&peer_id).map(Into::into),
+					ping_ms: session_info.ping_ms,
 				})
 			}).collect()
 		}).unwrap_or_else(Vec::new)